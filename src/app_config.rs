@@ -1,40 +1,530 @@
-use anyhow::{Context as _, Result};
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{bail, Context as _, Result};
 use config::Config;
-use serenity::model::prelude::ChannelId;
+use serenity::{
+    model::prelude::{ChannelId, GuildId, RoleId, UserId},
+    prelude::GatewayIntents,
+};
 
-#[derive(Debug, Default, serde::Deserialize, PartialEq, Clone)]
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
 pub struct DiscordConfig {
-    /// 投票の制限時間
+    /// 投票の制限時間 (サーバーごとの設定がない場合に使う)
     pub move_timeout_minutes: u64,
-    /// 最初の1人が移動してから他の人が移動するまでの時間
+    /// 投票の制限時間(秒) (設定した場合は move_timeout_minutes より優先する)
+    #[serde(default)]
+    pub move_timeout_seconds: Option<u64>,
+    /// 最初の1人が移動してからVCが作成されるのを待つ最大時間 (サーバーごとの設定がない場合に使う)
+    pub move_wait_seconds: u64,
+    /// VC作成チャンネル (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub vc_create_channel: Option<ChannelId>,
+    /// VC作成チャンネルでVCを作成できなかったときに使う予備のVC作成チャンネル (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub vc_create_fallback_channel: Option<ChannelId>,
+    /// Botが動作するカテゴリID (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub vc_category: Option<ChannelId>,
+    /// 無視するチャンネルID (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub vc_ignored_channels: Vec<ChannelId>,
+    /// dismissで全員を移動するロビーのVC (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub lobby_channel: Option<ChannelId>,
+    /// /moveで作成したVCの名前のテンプレート (サーバーごとの設定がない場合に使う)
+    #[serde(default)]
+    pub created_channel_name_template: Option<String>,
+    /// 移動後に移動先VCのチャットへ通知するか
+    #[serde(default)]
+    pub announce_in_destination: bool,
+    /// move_toで主催者自身も移動するか
+    #[serde(default = "default_true")]
+    pub move_requester: bool,
+    /// /moveのチャンネル名が空の場合に使うチャンネル名 ({requester}: 主催者名, {time}: 時刻)
+    #[serde(default)]
+    pub default_channel_name: Option<String>,
+    /// 同じVCからの募集を1つまでに制限するか
+    #[serde(default)]
+    pub one_vote_per_source_channel: bool,
+    /// 移動後に空になった移動元VCを削除するか
+    #[serde(default)]
+    pub cleanup_empty_source: bool,
+    /// MESSAGE_CONTENT (特権インテント) を要求するか (falseの場合、再起動前の募集メッセージへのリアクションは処理できない)
+    #[serde(default = "default_true")]
+    pub message_content_intent: bool,
+    /// GUILD_MEMBERS (特権インテント) を要求するか (falseの場合、lobby_channel と名前テンプレートの {organizer} は使えない)
+    #[serde(default = "default_true")]
+    pub guild_members_intent: bool,
+    /// 開発用サーバーID (設定した場合はグローバルではなくこのサーバーにコマンドを登録する。--register-only でも使う)
+    #[serde(default)]
+    pub dev_guild_id: Option<GuildId>,
+    /// 募集開始の返信を本人にのみ表示するか
+    #[serde(default = "default_true")]
+    pub confirmation_ephemeral: bool,
+    /// 動作確認コマンドでVCを作成するカテゴリID
+    #[serde(default)]
+    pub selftest_category: Option<ChannelId>,
+    /// 移動完了時に結果のメッセージに付与するリアクション (絵文字または<:名前:ID>形式)
+    #[serde(default)]
+    pub result_ack_emoji: Option<String>,
+    /// /moveで作成するチャンネル名に許可するパターン (正規表現)
+    #[serde(default)]
+    pub channel_name_pattern: Option<String>,
+    /// /moveで作成するチャンネル名に使用できない単語
+    #[serde(default)]
+    pub channel_name_blocklist: Vec<String>,
+    /// ステージチャンネルへ移動したときに主催者をスピーカーにするか
+    #[serde(default)]
+    pub promote_requester_on_stage: bool,
+    /// 募集開始から移動できるようになるまでの最低時間(秒)
+    #[serde(default)]
+    pub min_open_seconds: u64,
+    /// 募集メッセージをWebhookで投稿するか
+    #[serde(default)]
+    pub use_webhook: bool,
+    /// Webhookで投稿するときの名前
+    #[serde(default)]
+    pub webhook_name: Option<String>,
+    /// Webhookで投稿するときのアイコンのURL
+    #[serde(default)]
+    pub webhook_avatar_url: Option<String>,
+    /// リアクションを追加したユーザーを取得する最大人数
+    #[serde(default = "default_max_reactors")]
+    pub max_reactors: usize,
+    /// 移動後に主催者へ結果をDMで送信するか
+    #[serde(default)]
+    pub dm_summary_to_requester: bool,
+    /// 移動するメンバーを取得するときに同時に実行するAPIリクエスト数
+    #[serde(default = "default_max_concurrent_fetches")]
+    pub max_concurrent_fetches: usize,
+    /// メンバーを移動するときに同時に実行するAPIリクエスト数
+    #[serde(default = "default_max_concurrent_moves")]
+    pub max_concurrent_moves: usize,
+    /// メンバーの移動が一時的に失敗したとき (レート制限や通信エラーなど) に再試行する回数
+    #[serde(default = "default_move_retry_attempts")]
+    pub move_retry_attempts: u32,
+    /// リアクションで投票するか (falseの場合はボタンで投票する)
+    #[serde(default = "default_true")]
+    pub vote_with_reaction: bool,
+    /// 移動に必要な最低人数 (主催者を含む)
+    #[serde(default = "default_move_min_participants")]
+    pub move_min_participants: usize,
+    /// 募集を開始できるロール (空の場合は全員が開始できる)
+    #[serde(default)]
+    pub allowed_role_ids: Vec<RoleId>,
+    /// 募集で移動しないユーザー (配信者や録画用のアカウントなど。主催者も対象外にできる)
+    #[serde(default)]
+    pub move_exempt_user_ids: Vec<UserId>,
+    /// 募集で移動しないロール (このロールを持つメンバーは移動しない)
+    #[serde(default)]
+    pub move_exempt_role_ids: Vec<RoleId>,
+    /// 投票に使う絵文字 (絵文字または `<:名前:ID>` 形式のサーバー絵文字)
+    ///
+    /// 1つの文字列か、文字列のリストで指定する。リストの場合はどの絵文字でも参加として扱い、ボタンには最初の絵文字を使う
+    #[serde(default = "default_vote_emoji", deserialize_with = "one_or_many")]
+    pub vote_emoji: Vec<String>,
+    /// ユーザーに表示する言語
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// 新しく作成したVCの人数制限 (未設定または0の場合は制限なし)
+    #[serde(default)]
+    pub vc_user_limit: Option<u32>,
+    /// /moveで作成したVCが空になってから削除するまでの時間(秒) (未設定の場合は削除しない)
+    #[serde(default)]
+    pub empty_channel_grace_seconds: Option<u64>,
+    /// 1つのサーバーでBotが作成したVCを同時にいくつまで残せるか (未設定の場合は制限なし)
+    #[serde(default)]
+    pub max_created_channels_per_guild: Option<usize>,
+    /// /moveの募集開始時に主催者をVC作成チャンネルへ移動し、先にVCを作成しておくか
+    #[serde(default)]
+    pub move_organizer_on_start: bool,
+    /// VC作成チャンネル (自動でVCを作成する機能) を使わず、Botが設定したカテゴリにVCを作成するか
+    #[serde(default)]
+    pub create_channel_explicitly: bool,
+    /// Botが作成するカテゴリがいっぱいのとき、VC作成チャンネルでVCを作成して名前を変更するか
+    #[serde(default)]
+    pub category_full_fallback: bool,
+    /// 移動したメンバーに移動先をDMで通知するか
+    #[serde(default)]
+    pub dm_on_move: bool,
+    /// ステージチャンネルを移動先に指定できるようにするか
+    #[serde(default)]
+    pub allow_stage_channels: bool,
+    /// 同じユーザーが次に募集を開始できるまでの時間(秒) (0の場合は制限なし)
+    #[serde(default)]
+    pub move_cooldown_seconds: u64,
+    /// リアクションで投票する場合に、参加予定のメンバーを募集メッセージに表示するか
+    #[serde(default)]
+    pub show_participants_on_vote: bool,
+    /// 終了時 (Ctrl-C / SIGTERM) に進行中の募集メッセージを削除するか (削除しない場合は次回の起動時に再開する)
+    #[serde(default)]
+    pub cancel_votes_on_shutdown: bool,
+    /// 試行モード (確認と結果の表示のみ行い、メンバーの移動やVCの変更はしない)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Prometheusの統計を公開するポート (未設定の場合は公開しない)
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    /// 集計用の移動の記録 (move_event) のユーザーIDを隠すための秘密の文字列
+    ///
+    /// 設定するとユーザーIDをこの文字列を鍵にしたハッシュにして出力し、移動の理由は出力しない (未設定の場合はそのまま出力する)
+    #[serde(default)]
+    pub log_user_id_salt: Option<String>,
+    /// /moveで移動したVCの名前にVC作成チャンネルの名前が含まれる場合のみ名前を変更するか
+    #[serde(default)]
+    pub rename_only_default_named: bool,
+    /// move_toの移動先に指定できるカテゴリID (空の場合はすべてのカテゴリ)
+    #[serde(default)]
+    pub move_to_allowed_categories: Vec<ChannelId>,
+    /// 締め切りの何秒前に「まもなく募集を締め切ります」と表示するか (未設定の場合は表示しない)
+    #[serde(default)]
+    pub warn_before_timeout_seconds: Option<u64>,
+    /// 移動が完了したときに募集メッセージを削除するか (削除しない場合は完了した募集として残す)
+    #[serde(default = "default_true")]
+    pub delete_message_on_complete: bool,
+    /// 主催者以外の参加者の投票でも締め切って移動するか
+    #[serde(default)]
+    pub anyone_can_trigger: bool,
+    /// 参加者が最低人数に達した時点で、誰の投票かに関わらず締め切って移動するか
+    #[serde(default)]
+    pub auto_trigger_on_threshold: bool,
+    /// 募集を開始したチャンネルにスレッドを作成し、募集と結果をスレッドに投稿するか
+    #[serde(default)]
+    pub use_thread: bool,
+    /// 結果のメッセージの埋め込みの色 (設定ファイルには "#5865F2" のように16進数で書く)
+    #[serde(default, with = "hex_color")]
+    pub embed_color: Option<u32>,
+    /// 結果のメッセージの埋め込みのフッター
+    #[serde(default)]
+    pub embed_footer: Option<String>,
+}
+
+/// 1つの文字列と文字列のリストのどちらでも読み込む
+fn one_or_many<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// 色を16進数の文字列 ("#5865F2"、"0x5865F2"、"5865F2") として読み書きする
+mod hex_color {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    /// 16進数の文字列を色に変換する
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u32>, D::Error> {
+        let Some(text) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let hex = text
+            .trim()
+            .trim_start_matches('#')
+            .trim_start_matches("0x")
+            .trim_start_matches("0X");
+        match u32::from_str_radix(hex, 16) {
+            Ok(color) if hex.len() <= 6 => Ok(Some(color)),
+            _ => Err(D::Error::custom(format!(
+                "色は \"#5865F2\" のように6桁以内の16進数で指定してください: {}",
+                text
+            ))),
+        }
+    }
+
+    /// 色を16進数の文字列に変換する
+    pub fn serialize<S: Serializer>(color: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => serializer.serialize_str(&format!("#{:06X}", color)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// ユーザーに表示する言語のデフォルト値
+fn default_locale() -> String {
+    "ja".to_string()
+}
+
+/// 投票に使う絵文字のデフォルト値
+fn default_vote_emoji() -> Vec<String> {
+    vec!["🤚".to_string()]
+}
+
+/// 移動に必要な最低人数のデフォルト値
+fn default_move_min_participants() -> usize {
+    1
+}
+
+/// 移動するメンバーを取得するときに同時に実行するAPIリクエスト数のデフォルト値
+fn default_max_concurrent_fetches() -> usize {
+    4
+}
+
+/// メンバーを移動するときに同時に実行するAPIリクエスト数のデフォルト値
+fn default_max_concurrent_moves() -> usize {
+    4
+}
+
+/// メンバーの移動を再試行する回数のデフォルト値
+fn default_move_retry_attempts() -> u32 {
+    2
+}
+
+/// リアクションを追加したユーザーを取得する最大人数のデフォルト値
+fn default_max_reactors() -> usize {
+    1000
+}
+
+/// デフォルト値としてtrueを返す
+fn default_true() -> bool {
+    true
+}
+
+/// サーバーごとの設定 (設定しない項目は [discord] の設定を使う)
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
+pub struct GuildConfig {
+    /// 投票の制限時間
+    #[serde(default)]
+    pub move_timeout_minutes: Option<u64>,
+    /// 投票の制限時間(秒) (設定した場合は move_timeout_minutes より優先する)
+    #[serde(default)]
+    pub move_timeout_seconds: Option<u64>,
+    /// 最初の1人が移動してからVCが作成されるのを待つ最大時間
+    #[serde(default)]
+    pub move_wait_seconds: Option<u64>,
+    /// VC作成チャンネル
+    #[serde(default)]
+    pub vc_create_channel: Option<ChannelId>,
+    /// 予備のVC作成チャンネル
+    #[serde(default)]
+    pub vc_create_fallback_channel: Option<ChannelId>,
+    /// Botが動作するカテゴリID
+    #[serde(default)]
+    pub vc_category: Option<ChannelId>,
+    /// 無視するチャンネルID
+    #[serde(default)]
+    pub vc_ignored_channels: Option<Vec<ChannelId>>,
+    /// dismissで全員を移動するロビーのVC
+    #[serde(default)]
+    pub lobby_channel: Option<ChannelId>,
+    /// /moveで作成したVCの名前のテンプレート
+    #[serde(default)]
+    pub created_channel_name_template: Option<String>,
+}
+
+/// サーバーで使う設定 (サーバーごとの設定と [discord] の設定を合わせたもの)
+#[derive(Debug, PartialEq, Clone)]
+pub struct GuildSettings {
+    /// 投票の制限時間
+    pub move_timeout: Duration,
+    /// 最初の1人が移動してからVCが作成されるのを待つ最大時間
     pub move_wait_seconds: u64,
     /// VC作成チャンネル
     pub vc_create_channel: ChannelId,
+    /// 予備のVC作成チャンネル
+    pub vc_create_fallback_channel: Option<ChannelId>,
     /// Botが動作するカテゴリID
     pub vc_category: ChannelId,
     /// 無視するチャンネルID
     pub vc_ignored_channels: Vec<ChannelId>,
+    /// dismissで全員を移動するロビーのVC
+    pub lobby_channel: Option<ChannelId>,
+    /// /moveで作成したVCの名前のテンプレート ({name}: 入力したチャンネル名, {organizer}: 主催者名)
+    pub created_channel_name_template: Option<String>,
+}
+
+impl GuildSettings {
+    /// VCを作成するときに順に試すVC作成チャンネル (VC作成チャンネル、予備のVC作成チャンネルの順)
+    pub fn create_channels(&self) -> Vec<ChannelId> {
+        std::iter::once(self.vc_create_channel)
+            .chain(
+                self.vc_create_fallback_channel
+                    .filter(|channel_id| *channel_id != self.vc_create_channel),
+            )
+            .collect()
+    }
+
+    /// VC作成チャンネル (予備を含む) か
+    pub fn is_create_channel(&self, channel_id: ChannelId) -> bool {
+        channel_id == self.vc_create_channel || self.vc_create_fallback_channel == Some(channel_id)
+    }
+}
+
+/// メッセージの設定
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
+pub struct MessagesConfig {
+    /// 募集メッセージ ({}には順に主催者、移動先、単位付きの制限時間が入る。設定しない場合は言語ごとのメッセージ)
+    #[serde(default)]
+    pub vote_prompt: Option<String>,
+    /// 募集を開始したときの返信 ({}には順に参加する方法、移動先が入る。設定しない場合は言語ごとのメッセージ)
+    #[serde(default)]
+    pub vote_started: Option<String>,
+    /// 移動した結果のメッセージ ({}には順に移動したメンバーの説明、移動先が入る。設定しない場合は言語ごとのメッセージ)
+    #[serde(default)]
+    pub moved_result: Option<String>,
+    /// 誰も参加しないまま時間切れになったときのメッセージ (設定しない場合は言語ごとのメッセージ)
+    #[serde(default)]
+    pub vote_expired: Option<String>,
 }
 
 /// アプリケーションの設定
-#[derive(Debug, Default, serde::Deserialize, PartialEq, Clone)]
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
 pub struct AppConfig {
     /// Discordの設定
     pub discord: DiscordConfig,
+    /// メッセージの設定
+    #[serde(default)]
+    pub messages: MessagesConfig,
+    /// サーバーごとの設定 (サーバーIDがキー)
+    #[serde(default)]
+    pub guilds: HashMap<GuildId, GuildConfig>,
 }
 
 impl AppConfig {
+    /// サーバーで使う設定を取得
+    ///
+    /// VC作成チャンネルとカテゴリがサーバーごとの設定にも [discord] にもない場合はNone
+    pub fn guild(&self, guild_id: GuildId) -> Option<GuildSettings> {
+        let guild = self.guilds.get(&guild_id);
+        Some(GuildSettings {
+            move_timeout: self.move_timeout(guild_id),
+            move_wait_seconds: guild
+                .and_then(|guild| guild.move_wait_seconds)
+                .unwrap_or(self.discord.move_wait_seconds),
+            vc_create_channel: guild
+                .and_then(|guild| guild.vc_create_channel)
+                .or(self.discord.vc_create_channel)?,
+            vc_create_fallback_channel: guild
+                .and_then(|guild| guild.vc_create_fallback_channel)
+                .or(self.discord.vc_create_fallback_channel),
+            vc_category: guild
+                .and_then(|guild| guild.vc_category)
+                .or(self.discord.vc_category)?,
+            vc_ignored_channels: guild
+                .and_then(|guild| guild.vc_ignored_channels.clone())
+                .unwrap_or_else(|| self.discord.vc_ignored_channels.clone()),
+            lobby_channel: guild
+                .and_then(|guild| guild.lobby_channel)
+                .or(self.discord.lobby_channel),
+            created_channel_name_template: guild
+                .and_then(|guild| guild.created_channel_name_template.clone())
+                .or_else(|| self.discord.created_channel_name_template.clone()),
+        })
+    }
+
+    /// サーバーで使う投票の制限時間を取得
+    ///
+    /// サーバーごとの設定を [discord] の設定より優先し、同じ設定の中では秒の設定を分の設定より優先する
+    pub fn move_timeout(&self, guild_id: GuildId) -> Duration {
+        let guild = self.guilds.get(&guild_id);
+        let seconds = |seconds: Option<u64>, minutes: Option<u64>| {
+            seconds.or(minutes.map(|minutes| minutes * 60))
+        };
+        let seconds = guild
+            .and_then(|guild| seconds(guild.move_timeout_seconds, guild.move_timeout_minutes))
+            .or(seconds(
+                self.discord.move_timeout_seconds,
+                Some(self.discord.move_timeout_minutes),
+            ))
+            .unwrap_or_default();
+        Duration::from_secs(seconds)
+    }
+
+    /// 受け取るイベントの種類 (インテント) を取得
+    ///
+    /// 特権インテントは設定で要求しないようにできる
+    pub fn gateway_intents(&self) -> GatewayIntents {
+        let mut intents = GatewayIntents::non_privileged();
+        if self.discord.message_content_intent {
+            intents |= GatewayIntents::MESSAGE_CONTENT;
+        }
+        if self.discord.guild_members_intent {
+            intents |= GatewayIntents::GUILD_MEMBERS;
+        }
+        intents
+    }
+
+    /// 有効にした機能に必要なインテントを要求しているか確認
+    pub fn validate_intents(&self) -> Result<()> {
+        if self.discord.guild_members_intent {
+            return Ok(());
+        }
+        let guilds = || self.guilds.values();
+        // /dismiss はメンバーのキャッシュでBotを除いて移動するため、メンバーの一覧が必要
+        if self.discord.lobby_channel.is_some()
+            || guilds().any(|guild| guild.lobby_channel.is_some())
+        {
+            bail!("lobby_channel を使うには guild_members_intent を true にしてください");
+        }
+        // 主催者の表示名はメンバーのキャッシュから取得するため、メンバーの一覧が必要
+        if self
+            .discord
+            .created_channel_name_template
+            .iter()
+            .chain(guilds().filter_map(|guild| guild.created_channel_name_template.as_ref()))
+            .any(|template| template.contains("{organizer}"))
+        {
+            bail!("created_channel_name_template で {{organizer}} を使うには guild_members_intent を true にしてください");
+        }
+        Ok(())
+    }
+
+    /// 変更された設定の一覧を取得 (設定名, 変更前の値, 変更後の値)
+    ///
+    /// 設定名は `discord.dry_run` のように `.` で区切る
+    pub fn changes(&self, new: &AppConfig) -> Result<Vec<(String, String, String)>> {
+        let old = serde_json::to_value(self).context("設定の変換に失敗")?;
+        let new = serde_json::to_value(new).context("設定の変換に失敗")?;
+        let mut changes = Vec::new();
+        collect_changes("", &old, &new, &mut changes);
+        // 秘密の設定の値は表示しない (ログと返信に残さないため)
+        for (key, old, new) in &mut changes {
+            if SECRET_CONFIG.contains(&key.as_str()) {
+                for value in [old, new] {
+                    if value != "null" {
+                        *value = "\"***\"".to_string();
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
     /// 設定を読み込む
     pub fn load_config(basedir: &str) -> Result<AppConfig> {
-        // 設定ファイルのパス
-        let path = format!("{}/config.toml", basedir);
+        // 設定ファイルのリスト (後のファイルほど優先される)
+        // 環境変数 APP_CONFIG_FILES にカンマ区切りで指定でき、未指定の場合は config.toml → config.local.toml の順
+        let files = std::env::var("APP_CONFIG_FILES")
+            .unwrap_or_else(|_| "config.toml,config.local.toml".to_string());
+        let mut builder = Config::builder();
+        for (index, file) in files
+            .split(',')
+            .map(str::trim)
+            .filter(|file| !file.is_empty())
+            .enumerate()
+        {
+            // 設定ファイルのパス
+            let path = format!("{}/{}", basedir, file);
+            // 最初のファイルは必須、以降のファイルは存在する場合のみ読み込む
+            builder = builder.add_source(config::File::with_name(&path).required(index == 0));
+        }
         // 設定ファイルを読み込む
-        let config = Config::builder()
-            // Add in `./Settings.toml`
-            .add_source(config::File::with_name(&path))
+        let config = builder
             // Add in settings from the environment (with a prefix of APP)
             // Eg.. `APP_DEBUG=1 ./target/app` would set the `debug` key
-            .add_source(config::Environment::with_prefix("APP"))
+            // Eg.. `APP_MESSAGES__VOTE_PROMPT=...` would set the `messages.vote_prompt` key
+            .add_source(
+                config::Environment::with_prefix("APP")
+                    .prefix_separator("_")
+                    .separator("__"),
+            )
             .build()?;
         // 設定ファイルをパース
         let app_config = config
@@ -43,3 +533,156 @@ impl AppConfig {
         Ok(app_config)
     }
 }
+
+/// 値を表示しない秘密の設定
+const SECRET_CONFIG: &[&str] = &["discord.log_user_id_salt"];
+
+/// 2つの設定の値を比べ、変更された設定を集める (テーブルは中の設定ごとに比べる)
+fn collect_changes(
+    key: &str,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    changes: &mut Vec<(String, String, String)>,
+) {
+    use serde_json::Value;
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut names = old.keys().chain(new.keys()).collect::<Vec<&String>>();
+            names.sort();
+            names.dedup();
+            for name in names {
+                let child_key = if key.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", key, name)
+                };
+                collect_changes(
+                    &child_key,
+                    old.get(name).unwrap_or(&Value::Null),
+                    new.get(name).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (old, new) if old != new => {
+            changes.push((key.to_string(), old.to_string(), new.to_string()))
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 環境変数を変更するテストを同時に実行しないためのロック
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// 初期設定を config.toml として置いた設定フォルダを作成
+    fn test_basedir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!(
+            "discord-vc-vote-move-test-{}-config-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("設定フォルダの作成に失敗");
+        std::fs::write(
+            dir.join("config.toml"),
+            include_str!("../bot/config.default.toml"),
+        )
+        .expect("設定ファイルの書き込みに失敗");
+        dir.to_string_lossy().to_string()
+    }
+
+    /// config.local.toml で1つの設定だけを上書きし、ほかの設定は config.toml の値を引き継ぐ
+    #[test]
+    fn local_override_inherits_defaults() {
+        let _guard = ENV_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let basedir = test_basedir("local_override");
+        std::fs::write(
+            format!("{}/config.local.toml", basedir),
+            "[discord]\nmove_timeout_minutes = 3\n",
+        )
+        .unwrap();
+
+        let app_config = AppConfig::load_config(&basedir).unwrap();
+        assert_eq!(app_config.discord.move_timeout_minutes, 3);
+        assert_eq!(app_config.discord.move_wait_seconds, 10);
+        assert_eq!(
+            app_config.discord.default_channel_name.as_deref(),
+            Some("移動部屋-{requester}")
+        );
+        assert!(app_config.discord.vote_with_reaction);
+    }
+
+    /// 環境変数 APP_MESSAGES__VOTE_PROMPT は設定ファイルの vote_prompt より優先される
+    #[test]
+    fn env_overrides_vote_prompt() {
+        let _guard = ENV_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let basedir = test_basedir("env_override");
+        std::fs::write(
+            format!("{}/config.local.toml", basedir),
+            "[messages]\nvote_prompt = \"file {} {} {}\"\n",
+        )
+        .unwrap();
+
+        std::env::set_var("APP_MESSAGES__VOTE_PROMPT", "env {} {} {}");
+        let app_config = AppConfig::load_config(&basedir);
+        std::env::remove_var("APP_MESSAGES__VOTE_PROMPT");
+
+        let app_config = app_config.unwrap();
+        assert_eq!(
+            app_config.messages.vote_prompt.as_deref(),
+            Some("env {} {} {}")
+        );
+        assert_eq!(app_config.discord.move_timeout_minutes, 10);
+    }
+
+    /// VC作成チャンネル、予備のVC作成チャンネルの順に試し、同じチャンネルは2回試さない
+    #[test]
+    fn create_channels_try_fallback_after_primary() {
+        let mut app_config = AppConfig::default();
+        app_config.discord.vc_create_channel = Some(ChannelId(1));
+        app_config.discord.vc_category = Some(ChannelId(3));
+        let guild_id = GuildId(10);
+
+        let settings = app_config.guild(guild_id).unwrap();
+        assert_eq!(settings.create_channels(), vec![ChannelId(1)]);
+
+        app_config.discord.vc_create_fallback_channel = Some(ChannelId(2));
+        let settings = app_config.guild(guild_id).unwrap();
+        assert_eq!(settings.create_channels(), vec![ChannelId(1), ChannelId(2)]);
+        assert!(settings.is_create_channel(ChannelId(2)));
+
+        app_config.discord.vc_create_fallback_channel = Some(ChannelId(1));
+        let settings = app_config.guild(guild_id).unwrap();
+        assert_eq!(settings.create_channels(), vec![ChannelId(1)]);
+    }
+
+    /// 秘密の設定は変更されたことのみ表示し、値は表示しない
+    #[test]
+    fn changes_hide_secret_values() {
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.discord.log_user_id_salt = Some("secret".to_string());
+        new.discord.metrics_port = Some(9100);
+
+        let changes = old.changes(&new).unwrap();
+        assert!(changes.contains(&(
+            "discord.log_user_id_salt".to_string(),
+            "null".to_string(),
+            "\"***\"".to_string()
+        )));
+        assert!(changes.contains(&(
+            "discord.metrics_port".to_string(),
+            "null".to_string(),
+            "9100".to_string()
+        )));
+    }
+}
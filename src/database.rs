@@ -0,0 +1,36 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Context as _, Result};
+use rusqlite::Connection;
+
+/// Botのデータを保存するSQLiteのデータベース
+///
+/// テーブルの作成や読み書きは使う側のモジュールで行い、接続を共有する
+#[derive(Debug, Clone)]
+pub struct Database {
+    /// データベースへの接続
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    /// basedirの bot.db を開く (なければ作成)
+    pub fn open(basedir: &str) -> Result<Self> {
+        let connection = Connection::open(format!("{}/bot.db", basedir))
+            .context("データベースのファイルを開けません")?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// 接続を使って処理を実行
+    pub fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> Result<T> {
+        let connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        f(&connection).context("データベースの操作に失敗")
+    }
+}
@@ -0,0 +1,788 @@
+use std::sync::Arc;
+
+use serenity::{
+    async_trait,
+    builder::CreateMessage,
+    cache::Cache,
+    http::Http,
+    model::{
+        prelude::{
+            Channel, ChannelId, ChannelType, Guild, GuildChannel, GuildId, Member, Message,
+            MessageId, ReactionType, UserId, Webhook,
+        },
+        user::User,
+    },
+    Result,
+};
+
+/// 投票と移動の処理で使うDiscordの操作
+///
+/// 移動の処理をDiscordに接続せずに差し替えられるよう、serenityの呼び出しをまとめる
+#[async_trait]
+pub trait DiscordApi: Send + Sync {
+    /// Bot自身のユーザーID
+    fn current_user_id(&self, cache: &Cache) -> UserId;
+
+    /// キャッシュからサーバーを取得 (VCの参加状態はキャッシュにしかない)
+    fn guild(&self, cache: &Cache, guild_id: GuildId) -> Option<Guild>;
+
+    /// チャンネルを取得 (キャッシュになければAPIで取得)
+    async fn channel(
+        &self,
+        cache: &Arc<Cache>,
+        http: &Http,
+        channel_id: ChannelId,
+    ) -> Result<Channel>;
+
+    /// リアクションを追加したユーザーを取得 (afterのユーザーより後を最大limit人)
+    async fn reaction_users(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: ReactionType,
+        limit: u8,
+        after: Option<UserId>,
+    ) -> Result<Vec<User>>;
+
+    /// メッセージを削除
+    async fn delete_message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<()>;
+
+    /// メッセージを取得
+    async fn message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Message>;
+
+    /// メンバーをVCへ移動
+    async fn move_member(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_id: ChannelId,
+    ) -> Result<()>;
+
+    /// チャンネルの名前と人数制限を変更 (Noneの項目は変更しない)
+    async fn edit_channel(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        name: Option<String>,
+        user_limit: Option<u64>,
+    ) -> Result<()>;
+
+    /// メッセージを送信
+    async fn send_message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message: CreateMessage<'static>,
+    ) -> Result<Message>;
+
+    /// メッセージの本文を編集 (Webhookで投稿したメッセージはWebhookで編集する)
+    async fn edit_message(
+        &self,
+        http: &Http,
+        webhook: Option<&Webhook>,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<()>;
+
+    /// メッセージにリアクションを追加
+    async fn react(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: ReactionType,
+    ) -> Result<()>;
+
+    /// メンバーを取得 (キャッシュにないメンバーをAPIで取得)
+    async fn member(&self, http: &Http, guild_id: GuildId, user_id: UserId) -> Result<Member>;
+
+    /// カテゴリにVCを作成 (人数制限がNoneの場合は制限なし)
+    async fn create_voice_channel(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        name: &str,
+        category: ChannelId,
+        user_limit: Option<u32>,
+    ) -> Result<GuildChannel>;
+
+    /// チャンネルを削除
+    async fn delete_channel(&self, http: &Http, channel_id: ChannelId) -> Result<()>;
+
+    /// ステージチャンネルの聴衆をスピーカーにする
+    async fn unsuppress(&self, http: &Http, channel: &GuildChannel, user_id: UserId) -> Result<()>;
+
+    /// ユーザーにDMを送信
+    async fn send_dm(&self, http: &Http, user_id: UserId, content: &str) -> Result<()>;
+}
+
+/// serenityでDiscordのAPIを呼び出す実装
+#[derive(Debug, Default)]
+pub struct SerenityApi;
+
+#[async_trait]
+impl DiscordApi for SerenityApi {
+    fn current_user_id(&self, cache: &Cache) -> UserId {
+        cache.current_user_id()
+    }
+
+    fn guild(&self, cache: &Cache, guild_id: GuildId) -> Option<Guild> {
+        cache.guild(guild_id)
+    }
+
+    async fn channel(
+        &self,
+        cache: &Arc<Cache>,
+        http: &Http,
+        channel_id: ChannelId,
+    ) -> Result<Channel> {
+        channel_id.to_channel((cache, http)).await
+    }
+
+    async fn reaction_users(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: ReactionType,
+        limit: u8,
+        after: Option<UserId>,
+    ) -> Result<Vec<User>> {
+        http.get_reaction_users(
+            channel_id.0,
+            message_id.0,
+            &emoji,
+            limit,
+            after.map(|user_id| user_id.0),
+        )
+        .await
+    }
+
+    async fn delete_message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<()> {
+        channel_id.delete_message(http, message_id).await
+    }
+
+    async fn message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Message> {
+        channel_id.message(http, message_id).await
+    }
+
+    async fn move_member(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_id: ChannelId,
+    ) -> Result<()> {
+        guild_id
+            .move_member(http, user_id, channel_id)
+            .await
+            .map(|_| ())
+    }
+
+    async fn edit_channel(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        name: Option<String>,
+        user_limit: Option<u64>,
+    ) -> Result<()> {
+        channel_id
+            .edit(http, |c| {
+                if let Some(name) = name {
+                    c.name(name);
+                }
+                if let Some(limit) = user_limit {
+                    c.user_limit(limit);
+                }
+                c
+            })
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_message(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message: CreateMessage<'static>,
+    ) -> Result<Message> {
+        channel_id
+            .send_message(http, |m| {
+                *m = message;
+                m
+            })
+            .await
+    }
+
+    async fn edit_message(
+        &self,
+        http: &Http,
+        webhook: Option<&Webhook>,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) -> Result<()> {
+        match webhook {
+            Some(webhook) => webhook
+                .edit_message(http, message_id, |m| m.content(content))
+                .await
+                .map(|_| ()),
+            None => channel_id
+                .edit_message(http, message_id, |m| m.content(content))
+                .await
+                .map(|_| ()),
+        }
+    }
+
+    async fn react(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: ReactionType,
+    ) -> Result<()> {
+        channel_id.create_reaction(http, message_id, emoji).await
+    }
+
+    async fn member(&self, http: &Http, guild_id: GuildId, user_id: UserId) -> Result<Member> {
+        guild_id.member(http, user_id).await
+    }
+
+    async fn create_voice_channel(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        name: &str,
+        category: ChannelId,
+        user_limit: Option<u32>,
+    ) -> Result<GuildChannel> {
+        guild_id
+            .create_channel(http, |channel| {
+                channel
+                    .name(name)
+                    .kind(ChannelType::Voice)
+                    .category(category);
+                if let Some(limit) = user_limit {
+                    channel.user_limit(limit);
+                }
+                channel
+            })
+            .await
+    }
+
+    async fn delete_channel(&self, http: &Http, channel_id: ChannelId) -> Result<()> {
+        channel_id.delete(http).await.map(|_| ())
+    }
+
+    async fn unsuppress(&self, http: &Http, channel: &GuildChannel, user_id: UserId) -> Result<()> {
+        channel
+            .edit_voice_state(http, user_id, |voice_state| voice_state.suppress(false))
+            .await
+    }
+
+    async fn send_dm(&self, http: &Http, user_id: UserId, content: &str) -> Result<()> {
+        user_id
+            .create_dm_channel(http)
+            .await?
+            .send_message(http, |m| m.content(content))
+            .await
+            .map(|_| ())
+    }
+}
+
+/// テスト用にDiscordに接続せず、呼び出しを記録する実装
+#[cfg(test)]
+pub mod mock {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Mutex,
+    };
+
+    use serenity::model::prelude::Member;
+
+    use super::*;
+
+    /// 記録したDiscordの操作
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Call {
+        /// メンバーをVCへ移動 (失敗した移動も含む)
+        MoveMember(UserId, ChannelId),
+        /// チャンネルの名前と人数制限を変更
+        EditChannel(ChannelId, Option<String>, Option<u64>),
+        /// メッセージを送信 (送信したメッセージの本文)
+        SendMessage(ChannelId, String),
+        /// メッセージの本文を編集 (編集した本文)
+        EditMessage(MessageId, String),
+        /// メッセージにリアクションを追加
+        React(ChannelId, MessageId),
+        /// メッセージを削除
+        DeleteMessage(ChannelId, MessageId),
+        /// VCを作成 (作成したVCの名前)
+        CreateChannel(String),
+        /// チャンネルを削除
+        DeleteChannel(ChannelId),
+        /// ステージチャンネルの聴衆をスピーカーにする
+        Unsuppress(ChannelId, UserId),
+        /// DMを送信 (送信したDMの本文)
+        SendDm(UserId, String),
+    }
+
+    /// Discordの状態を持ち、操作を記録する実装
+    #[derive(Default)]
+    pub struct MockDiscordApi {
+        /// Bot自身のユーザーID
+        pub bot_id: UserId,
+        /// キャッシュにあるサーバー (移動するとVCの参加状態も変わる)
+        pub guild: Mutex<Option<Guild>>,
+        /// 取得できるチャンネル
+        pub channels: Mutex<HashMap<ChannelId, Channel>>,
+        /// 取得できるメッセージ
+        pub messages: Mutex<HashMap<MessageId, Message>>,
+        /// リアクションを追加したユーザー
+        pub reaction_users: Mutex<Vec<User>>,
+        /// 絵文字ごとにリアクションを追加したユーザー (絵文字がなければ reaction_users を返す)
+        pub emoji_reaction_users: Mutex<HashMap<String, Vec<User>>>,
+        /// 満員で移動できないVC (移動すると失敗する)
+        pub full_channels: Mutex<HashSet<ChannelId>>,
+        /// VC作成チャンネルと、そこへ移動したときに作成されて移動されるVC (VCを作成するBotの代わり)
+        pub created_channels: Mutex<HashMap<ChannelId, ChannelId>>,
+        /// チャンネル数が上限に達したカテゴリ (VCを作成すると失敗する)
+        pub full_categories: Mutex<HashSet<ChannelId>>,
+        /// 記録した操作
+        pub calls: Mutex<Vec<Call>>,
+    }
+
+    impl MockDiscordApi {
+        /// 記録した操作を取得
+        pub fn calls(&self) -> Vec<Call> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        /// 移動したメンバーと移動先を、移動した順に取得
+        pub fn moves(&self) -> Vec<(UserId, ChannelId)> {
+            self.calls()
+                .into_iter()
+                .filter_map(|call| match call {
+                    Call::MoveMember(user_id, channel_id) => Some((user_id, channel_id)),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        /// 操作を記録
+        fn record(&self, call: Call) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    /// カテゴリのチャンネル数が上限に達しているときにDiscordから返ってくるエラーを作成
+    pub fn category_full_error() -> serenity::Error {
+        serenity::Error::Http(Box::new(serenity::http::HttpError::UnsuccessfulRequest(
+            serenity::http::error::ErrorResponse {
+                status_code: serenity::http::StatusCode::BAD_REQUEST,
+                url: url::Url::parse("https://discord.com/api/v10").unwrap(),
+                error: serde_json::from_value(serde_json::json!({
+                    "code": 50035,
+                    "message": "Invalid Form Body",
+                    "errors": {
+                        "parent_id": {
+                            "_errors": [{
+                                "code": "CHANNEL_PARENT_MAX_CHANNELS",
+                                "message": "Maximum number of channels in category reached (50)",
+                            }],
+                        },
+                    },
+                }))
+                .unwrap(),
+            },
+        )))
+    }
+
+    /// テスト用のメッセージを作成
+    pub fn message(
+        message_id: MessageId,
+        channel_id: ChannelId,
+        author_id: UserId,
+        content: &str,
+    ) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": message_id.0.to_string(),
+            "channel_id": channel_id.0.to_string(),
+            "author": user(author_id, false),
+            "content": content,
+            "timestamp": "2022-10-01T00:00:00.000000+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .expect("テスト用のメッセージの作成に失敗")
+    }
+
+    /// テスト用のユーザーを作成
+    pub fn user(user_id: UserId, bot: bool) -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": user_id.0.to_string(),
+            "username": format!("user{}", user_id.0),
+            "discriminator": "0001",
+            "avatar": null,
+            "bot": bot,
+        }))
+        .expect("テスト用のユーザーの作成に失敗")
+    }
+
+    #[async_trait]
+    impl DiscordApi for MockDiscordApi {
+        fn current_user_id(&self, _cache: &Cache) -> UserId {
+            self.bot_id
+        }
+
+        fn guild(&self, _cache: &Cache, guild_id: GuildId) -> Option<Guild> {
+            self.guild
+                .lock()
+                .unwrap()
+                .clone()
+                .filter(|guild| guild.id == guild_id)
+        }
+
+        async fn channel(
+            &self,
+            _cache: &Arc<Cache>,
+            _http: &Http,
+            channel_id: ChannelId,
+        ) -> Result<Channel> {
+            self.channels
+                .lock()
+                .unwrap()
+                .get(&channel_id)
+                .cloned()
+                .ok_or(serenity::Error::Other("チャンネルがありません"))
+        }
+
+        async fn reaction_users(
+            &self,
+            _http: &Http,
+            _channel_id: ChannelId,
+            _message_id: MessageId,
+            emoji: ReactionType,
+            limit: u8,
+            after: Option<UserId>,
+        ) -> Result<Vec<User>> {
+            let users = self
+                .emoji_reaction_users
+                .lock()
+                .unwrap()
+                .get(&emoji.to_string())
+                .cloned()
+                .unwrap_or_else(|| self.reaction_users.lock().unwrap().clone());
+            let start = after
+                .and_then(|after| users.iter().position(|user| user.id == after))
+                .map_or(0, |position| position + 1);
+            Ok(users
+                .iter()
+                .skip(start)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn delete_message(
+            &self,
+            _http: &Http,
+            channel_id: ChannelId,
+            message_id: MessageId,
+        ) -> Result<()> {
+            self.record(Call::DeleteMessage(channel_id, message_id));
+            Ok(())
+        }
+
+        async fn message(
+            &self,
+            _http: &Http,
+            _channel_id: ChannelId,
+            message_id: MessageId,
+        ) -> Result<Message> {
+            self.messages
+                .lock()
+                .unwrap()
+                .get(&message_id)
+                .cloned()
+                .ok_or(serenity::Error::Other("メッセージがありません"))
+        }
+
+        async fn move_member(
+            &self,
+            _http: &Http,
+            _guild_id: GuildId,
+            user_id: UserId,
+            channel_id: ChannelId,
+        ) -> Result<()> {
+            // 実際のAPI呼び出しと同じように、移動中にほかの処理へ切り替わる機会を作る
+            tokio::task::yield_now().await;
+            self.record(Call::MoveMember(user_id, channel_id));
+            if self.full_channels.lock().unwrap().contains(&channel_id) {
+                return Err(serenity::Error::Other("VCが満員です"));
+            }
+            let channel_id = self
+                .created_channels
+                .lock()
+                .unwrap()
+                .get(&channel_id)
+                .copied()
+                .unwrap_or(channel_id);
+            if let Some(guild) = self.guild.lock().unwrap().as_mut() {
+                if let Some(voice_state) = guild.voice_states.get_mut(&user_id) {
+                    voice_state.channel_id = Some(channel_id);
+                }
+            }
+            Ok(())
+        }
+
+        async fn edit_channel(
+            &self,
+            _http: &Http,
+            channel_id: ChannelId,
+            name: Option<String>,
+            user_limit: Option<u64>,
+        ) -> Result<()> {
+            self.record(Call::EditChannel(channel_id, name, user_limit));
+            Ok(())
+        }
+
+        async fn send_message(
+            &self,
+            _http: &Http,
+            channel_id: ChannelId,
+            message: CreateMessage<'static>,
+        ) -> Result<Message> {
+            let content = message
+                .0
+                .get("content")
+                .and_then(|content| content.as_str())
+                .unwrap_or_default()
+                .to_string();
+            self.record(Call::SendMessage(channel_id, content.clone()));
+            let message_id = MessageId(self.calls.lock().unwrap().len() as u64);
+            Ok(self::message(message_id, channel_id, self.bot_id, &content))
+        }
+
+        async fn edit_message(
+            &self,
+            _http: &Http,
+            _webhook: Option<&Webhook>,
+            _channel_id: ChannelId,
+            message_id: MessageId,
+            content: &str,
+        ) -> Result<()> {
+            self.record(Call::EditMessage(message_id, content.to_string()));
+            Ok(())
+        }
+
+        async fn react(
+            &self,
+            _http: &Http,
+            channel_id: ChannelId,
+            message_id: MessageId,
+            _emoji: ReactionType,
+        ) -> Result<()> {
+            self.record(Call::React(channel_id, message_id));
+            Ok(())
+        }
+
+        async fn member(&self, _http: &Http, guild_id: GuildId, user_id: UserId) -> Result<Member> {
+            self.guild
+                .lock()
+                .unwrap()
+                .as_ref()
+                .filter(|guild| guild.id == guild_id)
+                .and_then(|guild| guild.members.get(&user_id).cloned())
+                .ok_or(serenity::Error::Other("メンバーがいません"))
+        }
+
+        async fn create_voice_channel(
+            &self,
+            _http: &Http,
+            guild_id: GuildId,
+            name: &str,
+            category: ChannelId,
+            user_limit: Option<u32>,
+        ) -> Result<GuildChannel> {
+            self.record(Call::CreateChannel(name.to_string()));
+            if self.full_categories.lock().unwrap().contains(&category) {
+                return Err(category_full_error());
+            }
+            let channel_id =
+                ChannelId(CREATED_CHANNEL_ID_START + self.calls.lock().unwrap().len() as u64);
+            let mut channel = voice_channel(
+                guild_id,
+                channel_id,
+                Some(category),
+                user_limit.map_or(0, u64::from),
+            );
+            if let Channel::Guild(guild_channel) = &mut channel {
+                guild_channel.name = name.to_string();
+            }
+            if let Some(guild) = self.guild.lock().unwrap().as_mut() {
+                guild.channels.insert(channel_id, channel.clone());
+            }
+            self.channels
+                .lock()
+                .unwrap()
+                .insert(channel_id, channel.clone());
+            channel
+                .guild()
+                .ok_or(serenity::Error::Other("VCを作成できません"))
+        }
+
+        async fn delete_channel(&self, _http: &Http, channel_id: ChannelId) -> Result<()> {
+            self.record(Call::DeleteChannel(channel_id));
+            self.channels.lock().unwrap().remove(&channel_id);
+            if let Some(guild) = self.guild.lock().unwrap().as_mut() {
+                guild.channels.remove(&channel_id);
+            }
+            Ok(())
+        }
+
+        async fn unsuppress(
+            &self,
+            _http: &Http,
+            channel: &GuildChannel,
+            user_id: UserId,
+        ) -> Result<()> {
+            self.record(Call::Unsuppress(channel.id, user_id));
+            Ok(())
+        }
+
+        async fn send_dm(&self, _http: &Http, user_id: UserId, content: &str) -> Result<()> {
+            self.record(Call::SendDm(user_id, content.to_string()));
+            Ok(())
+        }
+    }
+
+    /// 作成したVCに付けるIDの始まり (ほかのテスト用のIDと重ならないようにする)
+    const CREATED_CHANNEL_ID_START: u64 = 1000;
+
+    /// テスト用のボイスチャンネルを作成 (人数制限が0の場合は制限なし)
+    pub fn voice_channel(
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        parent_id: Option<ChannelId>,
+        user_limit: u64,
+    ) -> Channel {
+        serde_json::from_value(serde_json::json!({
+            "id": channel_id.0.to_string(),
+            "guild_id": guild_id.0.to_string(),
+            "type": 2,
+            "name": format!("vc{}", channel_id.0),
+            "position": 0,
+            "permission_overwrites": [],
+            "parent_id": parent_id.map(|parent_id| parent_id.0.to_string()),
+            "user_limit": user_limit,
+            "bitrate": 64000,
+            "nsfw": false,
+        }))
+        .expect("テスト用のチャンネルの作成に失敗")
+    }
+
+    /// テスト用のサーバーを作成 (IDがサーバーIDと同じ@everyoneロールに everyone_permissions の権限を付ける)
+    pub fn guild(
+        guild_id: GuildId,
+        owner_id: UserId,
+        everyone_permissions: u64,
+        channels: &[Channel],
+        members: &[Member],
+        voice_states: &[(UserId, ChannelId)],
+    ) -> Guild {
+        serde_json::from_value(serde_json::json!({
+            "id": guild_id.0.to_string(),
+            "name": "guild",
+            "owner_id": owner_id.0.to_string(),
+            "afk_timeout": 300,
+            "default_message_notifications": 0,
+            "explicit_content_filter": 0,
+            "verification_level": 0,
+            "mfa_level": 0,
+            "nsfw_level": 0,
+            "premium_tier": 0,
+            "system_channel_flags": 0,
+            "preferred_locale": "ja",
+            "joined_at": "2022-10-01T00:00:00.000000+00:00",
+            "large": false,
+            "member_count": members.len(),
+            "features": [],
+            "emojis": [],
+            "stickers": [],
+            "presences": [],
+            "roles": [{
+                "id": guild_id.0.to_string(),
+                "name": "@everyone",
+                "color": 0,
+                "hoist": false,
+                "managed": false,
+                "mentionable": false,
+                "permissions": everyone_permissions.to_string(),
+                "position": 0,
+            }],
+            "channels": channels,
+            "members": members,
+            "voice_states": voice_states
+                .iter()
+                .map(|(user_id, channel_id)| serde_json::json!({
+                    "guild_id": guild_id.0.to_string(),
+                    "channel_id": channel_id.0.to_string(),
+                    "user_id": user_id.0.to_string(),
+                    "session_id": "session",
+                    "deaf": false,
+                    "mute": false,
+                    "self_deaf": false,
+                    "self_mute": false,
+                    "self_video": false,
+                    "suppress": false,
+                    "request_to_speak_timestamp": null,
+                }))
+                .collect::<Vec<serde_json::Value>>(),
+        }))
+        .expect("テスト用のサーバーの作成に失敗")
+    }
+
+    /// テスト用のメンバーを作成
+    pub fn member(guild_id: GuildId, user_id: UserId, roles: &[u64]) -> Member {
+        serde_json::from_value(serde_json::json!({
+            "guild_id": guild_id.0.to_string(),
+            "user": user(user_id, false),
+            "roles": roles.iter().map(|role| role.to_string()).collect::<Vec<String>>(),
+            "joined_at": "2022-10-01T00:00:00.000000+00:00",
+            "deaf": false,
+            "mute": false,
+        }))
+        .expect("テスト用のメンバーの作成に失敗")
+    }
+}
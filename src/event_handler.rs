@@ -1,30 +1,52 @@
 use std::{
-    fmt::{Display, Formatter},
+    collections::{HashMap, HashSet},
+    future::Future,
     str::FromStr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, PoisonError,
+    },
+    time::{Duration, Instant},
 };
 
-use crate::app_config::AppConfig;
+use crate::{
+    app_config::{AppConfig, DiscordConfig, GuildSettings},
+    database::Database,
+    discord_api::DiscordApi,
+    metrics::Metrics,
+    move_history::MoveHistory,
+    strings::{self, Strings},
+    vote_store::VoteStore,
+};
 use anyhow::{anyhow, Context as _, Result};
+use chrono::Local;
 
 use dyn_fmt::AsStrFormatExt;
-use futures::future::try_join_all;
-use log::{error, warn};
+use futures::stream::{self, StreamExt as _, TryStreamExt as _};
+use log::{debug, error, info, warn};
+use rand::seq::SliceRandom as _;
 use regex::{Match, Regex};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
 use serenity::{
-    builder::ParseValue,
+    builder::{CreateApplicationCommand, CreateComponents, CreateMessage, ParseValue},
+    http::{Http, HttpError},
     json::Value,
     model::{
         application::command::Command,
         application::interaction::Interaction,
         gateway::Ready,
-        id::ChannelId,
+        id::{ChannelId, InteractionId},
         prelude::{
             command::CommandOptionType,
+            component::ButtonStyle,
             interaction::{
-                application_command::ApplicationCommandInteraction, InteractionResponseType,
+                application_command::ApplicationCommandInteraction,
+                autocomplete::AutocompleteInteraction,
+                message_component::MessageComponentInteraction, InteractionResponseType,
             },
-            ChannelType, CommandId, Reaction, UserId,
+            Channel, ChannelType, CommandId, Guild, GuildChannel, GuildId, Member, Message,
+            MessageId, Permissions, Reaction, ReactionType, RoleId, UserId, VoiceState, Webhook,
         },
         user::User,
     },
@@ -32,6 +54,7 @@ use serenity::{
 
 use serenity::async_trait;
 use serenity::prelude::*;
+use tokio::task::JoinHandle;
 
 #[derive(Clone, Debug)]
 /// コマンド
@@ -40,552 +63,7890 @@ struct Commands {
     move_command: CommandId,
     /// すでに作成されている部屋に移動コマンド
     move_to_command: CommandId,
+    /// 主催者のいる部屋に移動コマンド
+    move_here_command: CommandId,
+    /// 参加者をランダムに複数の部屋へ分けて移動コマンド
+    move_random_command: CommandId,
+    /// 指定したユーザーを投票なしで移動コマンド
+    move_users_command: CommandId,
+    /// VCの全員をロビーへ移動コマンド
+    dismiss_command: CommandId,
+    /// 動作確認コマンド
+    move_selftest_command: CommandId,
+    /// 進行中の募集の移動先を変更コマンド
+    move_retarget_command: CommandId,
+    /// 進行中の募集を取り消すコマンド
+    cancel_move_command: CommandId,
+    /// 進行中の投票の一覧コマンド
+    list_votes_command: CommandId,
+    /// 設定を再読み込みするコマンド
+    reload_config_command: CommandId,
+    /// 移動の統計コマンド
+    move_stats_command: CommandId,
+    /// 新しい募集の受け付けを停止・再開するコマンド
+    set_enabled_command: CommandId,
+    /// 誰もいないVCを削除するコマンド
+    cleanup_channels_command: CommandId,
 }
 
 // コマンドの種類
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum CommandType {
     Move(String),
     MoveTo(ChannelId),
+    /// 主催者が募集を開始したときにいたVCへ移動する
+    MoveHere(ChannelId),
+    /// 指定した数の新しいVCを作成し、参加者をランダムに分けて移動する
+    MoveRandom(usize),
 }
 
-impl Display for CommandType {
-    /// 文字列に変換
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+/// 投票の絵文字を押したときの動作 (今はすべての絵文字が参加だが、絵文字ごとに動作を分けられるようにしている)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum VoteAction {
+    /// 募集に参加する
+    Join,
+}
+
+/// 移動できるメンバーの制限 (主催者が指定したユーザーまたはロール)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum LimitTo {
+    User(UserId),
+    Role(RoleId),
+}
+
+impl LimitTo {
+    /// メンバーが移動できるか
+    fn allows(&self, member: &Member) -> bool {
+        match self {
+            LimitTo::User(user_id) => member.user.id == *user_id,
+            LimitTo::Role(role_id) => member.roles.contains(role_id),
+        }
+    }
+
+    /// メンションに変換
+    fn mention(&self) -> String {
         match self {
-            CommandType::Move(channel_name) => write!(f, "新規VC「{channel_name}」"),
-            CommandType::MoveTo(channel_id) => write!(f, "{}", channel_id.mention().to_string()),
+            LimitTo::User(user_id) => user_id.mention().to_string(),
+            LimitTo::Role(role_id) => role_id.mention().to_string(),
         }
     }
 }
 
 impl CommandType {
+    /// 文字列に変換
+    fn to_text(&self, strings: &Strings) -> String {
+        match self {
+            CommandType::Move(channel_name) => strings.new_channel.format(&[channel_name]),
+            CommandType::MoveTo(channel_id) | CommandType::MoveHere(channel_id) => {
+                channel_id.mention().to_string()
+            }
+            CommandType::MoveRandom(count) => strings.random_rooms.format(&[count]),
+        }
+    }
+
     /// 文字列から変換
     fn parse(move_to_match: Option<Match>, move_match: Option<Match>) -> Option<Self> {
         move_to_match
             .and_then(|m| {
-                ChannelId::from_str(m.as_str())
+                m.as_str()
+                    .parse::<u64>()
                     .ok()
-                    .map(|channel_id| CommandType::MoveTo(channel_id))
+                    .map(|id| CommandType::MoveTo(ChannelId(id)))
             })
-            .or_else(|| move_match.and_then(|m| Some(CommandType::Move(m.as_str().to_string()))))
+            .or_else(|| move_match.map(|m| CommandType::Move(m.as_str().to_string())))
+    }
+}
+
+/// 進行中の投票
+#[derive(Clone, Debug)]
+struct Vote {
+    /// 募集メッセージを投稿したサーバー
+    guild_id: GuildId,
+    /// 募集メッセージを投稿したチャンネル
+    channel_id: ChannelId,
+    /// 募集を開始したユーザー
+    requester: UserId,
+    /// 移動先
+    command_type: CommandType,
+    /// 募集メッセージの前置き (メンションや募集メッセージ、理由)
+    header: String,
+    /// 募集を開始したVC
+    source_channel_id: ChannelId,
+    /// 募集を開始したVCのほかに一緒に集めるVC
+    additional_source_channel_ids: Vec<ChannelId>,
+    /// リアクションを追加したユーザー (追加した順)
+    reactors: Vec<UserId>,
+    /// 移動の理由
+    reason: Option<String>,
+    /// 募集を開始した時刻
+    created_at: Instant,
+    /// 募集メッセージを投稿したWebhook (Botとして投稿した場合はNone)
+    webhook: Option<Webhook>,
+    /// 移動後に元のVCへ戻るまでの時間(分)
+    return_after_minutes: Option<u64>,
+    /// 募集開始時に主催者を移動した先のVC
+    organizer_room: Option<ChannelId>,
+    /// 投票の制限時間
+    timeout: Duration,
+    /// まもなく締め切ることを募集メッセージに表示したか
+    closing_soon: bool,
+    /// 移動できるメンバーの制限
+    limit_to: Option<LimitTo>,
+    /// 移動後もあとから参加した人を移動する時間
+    follow_duration: Option<Duration>,
+    /// 締め切りを開始したか (同時に締め切ろうとしても移動は1回だけ行うため)
+    triggered: bool,
+}
+
+impl Vote {
+    /// 主催者を含めた参加者の人数
+    fn participant_count(&self) -> usize {
+        1 + self
+            .reactors
+            .iter()
+            .filter(|id| **id != self.requester)
+            .count()
+    }
+
+    /// メンバーを集めるVC (募集を開始したVC、一緒に集めるVCの順)
+    fn source_channels(&self) -> Vec<ChannelId> {
+        std::iter::once(self.source_channel_id)
+            .chain(self.additional_source_channel_ids.iter().copied())
+            .collect()
+    }
+}
+
+/// 移動後もあとから参加した人を移動している募集
+#[derive(Clone, Debug)]
+struct Follow {
+    /// 移動したサーバー
+    guild_id: GuildId,
+    /// 移動先
+    to_channel_id: ChannelId,
+    /// 移動できるメンバーの制限
+    limit_to: Option<LimitTo>,
+    /// 移動したユーザー (同じユーザーを何度も移動しないため)
+    moved: HashSet<UserId>,
+    /// 募集メッセージを投稿したチャンネル
+    channel_id: ChannelId,
+    /// 募集メッセージを投稿したWebhook
+    webhook: Option<Webhook>,
+    /// 募集メッセージの前置き
+    header: String,
+}
+
+/// ファイルに保存する投票 (再起動後に募集を再開するため)
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredVote {
+    /// 募集メッセージ
+    message_id: MessageId,
+    /// 募集メッセージを投稿したサーバー
+    guild_id: GuildId,
+    /// 募集メッセージを投稿したチャンネル
+    channel_id: ChannelId,
+    /// 募集を開始したユーザー
+    requester: UserId,
+    /// 移動先
+    command_type: CommandType,
+    /// 募集メッセージの前置き
+    header: String,
+    /// 募集を開始したVC
+    source_channel_id: ChannelId,
+    /// 募集を開始したVCのほかに一緒に集めるVC
+    #[serde(default)]
+    additional_source_channel_ids: Vec<ChannelId>,
+    /// リアクションを追加したユーザー (追加した順)
+    reactors: Vec<UserId>,
+    /// 移動の理由
+    reason: Option<String>,
+    /// 移動後に元のVCへ戻るまでの時間(分)
+    return_after_minutes: Option<u64>,
+    /// Webhookで投稿したか
+    use_webhook: bool,
+    /// 募集開始時に主催者を移動した先のVC
+    #[serde(default)]
+    organizer_room: Option<ChannelId>,
+    /// まもなく締め切ることを募集メッセージに表示したか
+    #[serde(default)]
+    closing_soon: bool,
+    /// 移動できるメンバーの制限
+    #[serde(default)]
+    limit_to: Option<LimitTo>,
+    /// 移動後もあとから参加した人を移動する時間(秒)
+    #[serde(default)]
+    follow_duration_seconds: Option<u64>,
+    /// 募集を開始した時刻 (UNIX時間)
+    created_at: i64,
+    /// 締め切り時刻 (UNIX時間)
+    deadline: i64,
+}
+
+impl StoredVote {
+    /// 進行中の投票から変換
+    fn new(message_id: MessageId, vote: &Vote) -> Self {
+        let created_at = Local::now().timestamp() - vote.created_at.elapsed().as_secs() as i64;
+        Self {
+            message_id,
+            guild_id: vote.guild_id,
+            channel_id: vote.channel_id,
+            requester: vote.requester,
+            command_type: vote.command_type.clone(),
+            header: vote.header.clone(),
+            source_channel_id: vote.source_channel_id,
+            additional_source_channel_ids: vote.additional_source_channel_ids.clone(),
+            reactors: vote.reactors.clone(),
+            reason: vote.reason.clone(),
+            return_after_minutes: vote.return_after_minutes,
+            use_webhook: vote.webhook.is_some(),
+            organizer_room: vote.organizer_room,
+            closing_soon: vote.closing_soon,
+            limit_to: vote.limit_to,
+            follow_duration_seconds: vote.follow_duration.map(|duration| duration.as_secs()),
+            created_at,
+            deadline: created_at + vote.timeout.as_secs() as i64,
+        }
+    }
+}
+
+/// 移動後に元のVCへ戻す予定 (再起動後も戻せるようにファイルに保存する)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PendingReturn {
+    /// 移動した募集メッセージ
+    message_id: MessageId,
+    /// 移動したサーバー
+    guild_id: GuildId,
+    /// 結果を送信するチャンネル
+    text_channel_id: ChannelId,
+    /// 移動先のVC
+    to_channel_id: ChannelId,
+    /// 戻す先のVC (募集を開始したVC)
+    source_channel_id: ChannelId,
+    /// 戻すユーザー
+    user_ids: Vec<UserId>,
+    /// 戻す時刻 (UNIX時間)
+    deadline: i64,
+}
+
+/// 元のVCへ戻す予定と、戻す処理 (募集メッセージのIDがキー)
+type ScheduledReturns = HashMap<MessageId, (PendingReturn, JoinHandle<()>)>;
+
+/// 募集を開始しているところのチャンネルとVC (コマンドのインタラクションIDがキー)
+type StartingVotes = HashMap<InteractionId, (ChannelId, Vec<ChannelId>)>;
+
+/// 募集を開始しているところの予約 (開始し終えたときも失敗したときも、破棄したときに予約を外す)
+struct VoteReservation {
+    /// 募集を開始しているところのチャンネルとVC
+    starting_votes: Arc<std::sync::Mutex<StartingVotes>>,
+    /// 予約したコマンドのインタラクションID
+    interaction_id: InteractionId,
+}
+
+impl Drop for VoteReservation {
+    fn drop(&mut self) {
+        self.starting_votes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.interaction_id);
     }
 }
 
+/// リアクションの識別子 (メッセージ, ユーザー, 絵文字)
+type ReactionKey = (MessageId, UserId, String);
+
 /// イベント受信リスナー
 pub struct Handler {
-    /// 設定
-    app_config: AppConfig,
+    /// 設定 (reload_configで置き換える)
+    app_config: Arc<std::sync::RwLock<Arc<AppConfig>>>,
+    /// 設定ファイルのディレクトリ
+    basedir: String,
+    /// 投票と移動の処理で使うDiscordの操作
+    discord: Arc<dyn DiscordApi>,
     /// 登録したコマンドのID
     move_command_id: Arc<Mutex<Option<Commands>>>,
     /// 募集メッセージ
     vote_message: String,
-    /// 募集メッセージの正規表現
-    vote_message_regex: Regex,
+    /// 募集メッセージの正規表現 (今の募集メッセージ、以前の募集メッセージの順)
+    vote_message_regexes: Vec<Regex>,
+    /// 進行中の投票 (募集メッセージのIDがキー)
+    votes: Arc<Mutex<HashMap<MessageId, Vote>>>,
+    /// 募集を開始しているところのチャンネルとVC (募集メッセージを投稿して投票を記録するまで)
+    starting_votes: Arc<std::sync::Mutex<StartingVotes>>,
+    /// 時間切れで募集メッセージを削除する処理 (募集メッセージのIDがキー)
+    ///
+    /// 移動後もあとから参加した人を移動している間は、その時間が経ったときに募集メッセージを片付ける処理
+    vote_timers: Arc<Mutex<HashMap<MessageId, JoinHandle<()>>>>,
+    /// 移動後もあとから参加した人を移動している募集 (募集メッセージのIDがキー)
+    follows: Arc<Mutex<HashMap<MessageId, Follow>>>,
+    /// 元のVCへ戻す予定
+    returns: Arc<Mutex<ScheduledReturns>>,
+    /// ユーザーメンションの正規表現
+    user_mention_regex: Regex,
+    /// ユーザーに表示する文字列
+    strings: &'static Strings,
+    /// ボタンに使う投票の絵文字 (vote_emojisの最初の絵文字)
+    vote_emoji: ReactionType,
+    /// 投票に使う絵文字
+    vote_emojis: Vec<ReactionType>,
+    /// 最近処理したリアクション (重複したイベントを無視するため)
+    recent_reactions: Arc<Mutex<HashMap<ReactionKey, Instant>>>,
+    /// 募集メッセージを投稿するWebhook (チャンネルIDがキー)
+    webhooks: Arc<Mutex<HashMap<ChannelId, Webhook>>>,
+    /// 進行中の投票の保存先
+    vote_store: VoteStore,
+    /// 完了した移動の記録
+    move_history: MoveHistory,
+    /// /moveで作成したVC (空になったら削除する)
+    created_channels: Arc<Mutex<HashSet<ChannelId>>>,
+    /// Botが作成して残っているVC (サーバーごと、作成できるVCの数を制限するため)
+    guild_created_channels: Arc<Mutex<HashMap<GuildId, HashSet<ChannelId>>>>,
+    /// 応答を遅延したコマンド (エラーを返信するときに、遅延した応答を編集するため)
+    deferred_interactions: Arc<Mutex<HashSet<InteractionId>>>,
+    /// 最後に募集を開始した時刻 (サーバーとユーザーがキー)
+    move_cooldowns: Arc<Mutex<HashMap<(GuildId, UserId), Instant>>>,
+    /// 参加予定の人数の編集を待っている募集メッセージ
+    pending_vote_edits: Arc<Mutex<HashSet<MessageId>>>,
+    /// 統計
+    metrics: Arc<Metrics>,
+    /// 保存した投票を再開したか (再開していれば進行中の投票はすべて記録されている)
+    votes_resumed: AtomicBool,
+    /// 新しい募集を受け付けるか (set_enabledで切り替える)
+    enabled: AtomicBool,
+    /// 設定したVC作成チャンネルやカテゴリに誤りがあるサーバー (募集を開始できない)
+    misconfigured_guilds: Arc<Mutex<HashSet<GuildId>>>,
+}
+
+/// 終了時に進行中の投票を片付ける処理 (イベント受信リスナーをBotに渡した後も使えるよう、投票の状態を共有する)
+pub struct VoteShutdown {
+    /// 進行中の投票
+    votes: Arc<Mutex<HashMap<MessageId, Vote>>>,
+    /// 時間切れで募集メッセージを削除する処理
+    vote_timers: Arc<Mutex<HashMap<MessageId, JoinHandle<()>>>>,
+    /// 元のVCへ戻す予定
+    returns: Arc<Mutex<ScheduledReturns>>,
+    /// 移動後もあとから参加した人を移動している募集
+    follows: Arc<Mutex<HashMap<MessageId, Follow>>>,
+    /// ユーザーに表示する文字列
+    strings: &'static Strings,
+    /// 進行中の投票の保存先
+    vote_store: VoteStore,
+    /// 設定 (進行中の募集メッセージを削除するかの確認に使う)
+    app_config: Arc<std::sync::RwLock<Arc<AppConfig>>>,
+    /// 統計
+    metrics: Arc<Metrics>,
+}
+
+impl VoteShutdown {
+    /// 時間切れで削除する処理 (締め切り前の通知を含む) と元のVCへ戻す処理を止め、設定により進行中の募集メッセージを削除する
+    ///
+    /// 削除しない場合は保存した投票を次回の起動時に再開する (元のVCへ戻す予定は常に次回の起動時に再開する)。
+    /// あとから参加した人の移動は再開しないため、募集メッセージに終了したことを表示する
+    pub async fn shutdown(&self, http: &Http) {
+        for (_, timer) in self.vote_timers.lock().await.drain() {
+            timer.abort();
+        }
+        for (_, (_, task)) in self.returns.lock().await.drain() {
+            task.abort();
+        }
+        let follows = std::mem::take(&mut *self.follows.lock().await);
+        for (message_id, follow) in follows {
+            let content = format!("{}\n\n{}", follow.header, self.strings.follow_cancelled);
+            let result = match &follow.webhook {
+                Some(webhook) => webhook
+                    .edit_message(http, message_id, |m| m.content(&content).components(|c| c))
+                    .await
+                    .map(|_| ()),
+                None => follow
+                    .channel_id
+                    .edit_message(http, message_id, |m| m.content(&content).components(|c| c))
+                    .await
+                    .map(|_| ()),
+            };
+            if let Err(why) = result {
+                warn!("終了時の移動後の募集メッセージの編集に失敗: {:?}", why);
+            }
+        }
+        let cancel_votes = self
+            .app_config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .discord
+            .cancel_votes_on_shutdown;
+        if !cancel_votes {
+            return;
+        }
+
+        let votes = std::mem::take(&mut *self.votes.lock().await);
+        save_votes(&self.vote_store, &HashMap::new(), &self.metrics);
+        for (message_id, vote) in votes {
+            let result = match &vote.webhook {
+                Some(webhook) => webhook.delete_message(http, message_id).await,
+                None => vote.channel_id.delete_message(http, message_id).await,
+            };
+            if let Err(why) = result {
+                warn!("終了時の募集メッセージの削除に失敗: {:?}", why);
+            }
+        }
+        info!("終了のため進行中の募集を取り消しました");
+    }
 }
 
 impl Handler {
+    /// 終了時に進行中の投票を片付ける処理を取得
+    pub fn vote_shutdown(&self) -> VoteShutdown {
+        VoteShutdown {
+            votes: self.votes.clone(),
+            vote_timers: self.vote_timers.clone(),
+            returns: self.returns.clone(),
+            follows: self.follows.clone(),
+            strings: self.strings,
+            vote_store: self.vote_store.clone(),
+            app_config: self.app_config.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+
+    /// 統計を取得
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    /// 現在の設定を取得 (再読み込みされても、取得した設定は処理が終わるまで変わらない)
+    fn app_config(&self) -> Arc<AppConfig> {
+        self.app_config
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
     /// コンストラクタ
-    pub fn new(app_config: AppConfig) -> Result<Self> {
-        let vote_message = "{}が一緒に移動する人の募集を開始しました。\n{}に移動したい人は{}分以内にリアクション押してください！";
-        let vote_message_escape =
-            regex::escape(&vote_message.replace("{}", "%s")).replace("%s", "{}");
-        let vote_message_with_regex =
-            vote_message_escape.format(&[r"<@(\d+)>", r"(?:<#(\d+)>|新規VC「(.+)」)", r"(?:\d+)"]);
-        let vote_message_regex = Regex::new(&format!("{vote_message_with_regex}$"))
-            .context("募集メッセージの正規表現のコンパイルに失敗")?;
+    pub fn new(app_config: AppConfig, basedir: &str, discord: Arc<dyn DiscordApi>) -> Result<Self> {
+        let (strings, vote_message, vote_message_regexes, vote_emoji, vote_emojis) =
+            parse_vote_format(&app_config)?;
+        result_ack_emoji(&app_config)?;
+        channel_name_pattern(&app_config)?;
+        validate_message_templates(&app_config)?;
+        app_config.validate_intents()?;
         Ok(Self {
-            app_config,
+            app_config: Arc::new(std::sync::RwLock::new(Arc::new(app_config))),
+            basedir: basedir.to_string(),
+            discord,
             move_command_id: Arc::new(Mutex::new(None)),
-            vote_message: vote_message.to_string(),
-            vote_message_regex,
+            vote_message,
+            vote_message_regexes,
+            votes: Arc::new(Mutex::new(HashMap::new())),
+            starting_votes: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            vote_timers: Arc::new(Mutex::new(HashMap::new())),
+            follows: Arc::new(Mutex::new(HashMap::new())),
+            returns: Arc::new(Mutex::new(HashMap::new())),
+            user_mention_regex: Regex::new(r"<@!?([0-9]{1,20})>")
+                .context("メンションの正規表現のコンパイルに失敗")?,
+            strings,
+            vote_emoji,
+            vote_emojis,
+            recent_reactions: Arc::new(Mutex::new(HashMap::new())),
+            webhooks: Arc::new(Mutex::new(HashMap::new())),
+            vote_store: VoteStore::new(basedir),
+            move_history: MoveHistory::new(Database::open(basedir)?)?,
+            created_channels: Arc::new(Mutex::new(HashSet::new())),
+            guild_created_channels: Arc::new(Mutex::new(HashMap::new())),
+            deferred_interactions: Arc::new(Mutex::new(HashSet::new())),
+            move_cooldowns: Arc::new(Mutex::new(HashMap::new())),
+            pending_vote_edits: Arc::new(Mutex::new(HashSet::new())),
+            metrics: Arc::new(Metrics::default()),
+            votes_resumed: AtomicBool::new(false),
+            enabled: AtomicBool::new(true),
+            misconfigured_guilds: Arc::new(Mutex::new(HashSet::new())),
         })
     }
 
-    /// コマンドが呼ばれたときの処理
-    async fn register_command(&self, ctx: &Context) -> Result<()> {
+    /// 募集メッセージから送信者と移動先を読み取る
+    ///
+    /// 任意の文字列を受け付け、形式が正しくない場合はパニックせずにエラーを返す
+    fn parse_vote_message(&self, content: &str) -> Result<(UserId, CommandType)> {
+        let caps = self
+            .vote_message_regexes
+            .iter()
+            .find_map(|regex| regex.captures(content))
+            .context("メッセージのパースに失敗")?;
+        let mention_user = caps
+            .get(1)
+            .and_then(|m| m.as_str().parse::<u64>().ok())
+            .map(UserId)
+            .context("送信者のメンション取得に失敗")?;
+        let command_type = CommandType::parse(caps.get(2), caps.get(3))
+            .context("移動先VCのチャンネル取得に失敗")?;
+        Ok((mention_user, command_type))
+    }
+
+    /// 募集メッセージを投稿するWebhookを取得 (なければ作成)
+    async fn channel_webhook(&self, ctx: &Context, channel_id: ChannelId) -> Result<Webhook> {
+        let mut webhooks = self.webhooks.lock().await;
+        if let Some(webhook) = webhooks.get(&channel_id) {
+            return Ok(webhook.clone());
+        }
+
+        // Botが作成したWebhookがあれば再利用し、なければ作成
+        let bot_id = self.discord.current_user_id(&ctx.cache);
+        let existing = channel_id
+            .webhooks(&ctx)
+            .await
+            .context("Webhookの取得に失敗")?
+            .into_iter()
+            .find(|webhook| {
+                webhook.token.is_some() && webhook.user.as_ref().map(|user| user.id) == Some(bot_id)
+            });
+        let webhook = match existing {
+            Some(webhook) => webhook,
+            None => {
+                let app_config = self.app_config();
+                let name = app_config
+                    .discord
+                    .webhook_name
+                    .as_deref()
+                    .unwrap_or(self.strings.webhook_name);
+                channel_id
+                    .create_webhook(&ctx, name)
+                    .await
+                    .context("Webhookの作成に失敗 (Webhookの管理権限が必要です)")?
+            }
+        };
+        webhooks.insert(channel_id, webhook.clone());
+        Ok(webhook)
+    }
+
+    /// 投票の状態から募集メッセージの本文を構築
+    fn vote_content(&self, vote: &Vote) -> String {
+        vote_content(vote, &self.vote_message, self.strings, &self.app_config())
+    }
+
+    /// 募集メッセージを投票の状態に合わせて編集
+    async fn edit_vote_message(
+        &self,
+        ctx: &Context,
+        message_id: MessageId,
+        vote: &Vote,
+    ) -> Result<()> {
+        edit_vote_message(
+            &*self.discord,
+            ctx,
+            message_id,
+            vote,
+            self.vote_content(vote),
+        )
+        .await
+    }
+
+    /// 参加予定の人数が変わった募集メッセージを少し待ってから編集する
+    ///
+    /// 続けてリアクションされても編集は1回にまとめる (APIのレート制限を避けるため)
+    async fn update_vote_count(&self, ctx: &Context, message_id: MessageId) -> Result<()> {
+        // すでに編集を待っていれば、その編集で最新の人数が反映される
+        if !self.pending_vote_edits.lock().await.insert(message_id) {
+            return Ok(());
+        }
+        tokio::time::sleep(VOTE_EDIT_DEBOUNCE).await;
+        self.pending_vote_edits.lock().await.remove(&message_id);
+
+        // 待っている間に締め切られていれば編集しない
+        let vote = match self.votes.lock().await.get(&message_id).cloned() {
+            Some(vote) => vote,
+            None => return Ok(()),
+        };
+        self.edit_vote_message(ctx, message_id, &vote).await
+    }
+
+    /// 作成するチャンネル名が許可されているか確認
+    fn validate_channel_name(&self, channel_name: &str) -> Result<()> {
+        // Discordのチャンネル名の長さの制限を確認
+        if channel_name.chars().count() > CHANNEL_NAME_MAX_LENGTH {
+            return Err(anyhow!(self
+                .strings
+                .channel_name_too_long
+                .format(&[CHANNEL_NAME_MAX_LENGTH])));
+        }
+
+        // 改行などの制御文字を含んでいないか確認 (募集メッセージの読み取りにも影響するため)
+        if channel_name.chars().any(char::is_control) {
+            return Err(anyhow!(self.strings.channel_name_invalid_chars));
+        }
+
+        // 許可するパターンに一致するか確認
+        if let Some(pattern) = channel_name_pattern(&self.app_config())? {
+            if !pattern.is_match(channel_name) {
+                return Err(anyhow!(self.strings.channel_name_not_allowed));
+            }
+        }
+
+        // 禁止されている単語を含んでいないか確認
+        let lower_name = channel_name.to_lowercase();
+        if self
+            .app_config()
+            .discord
+            .channel_name_blocklist
+            .iter()
+            .any(|word| lower_name.contains(&word.to_lowercase()))
+        {
+            return Err(anyhow!(self.strings.channel_name_blocked));
+        }
+
+        Ok(())
+    }
+
+    /// チャンネル名が空の場合に使うデフォルトのチャンネル名を生成
+    fn default_channel_name(&self, interaction: &ApplicationCommandInteraction) -> Result<String> {
+        let app_config = self.app_config();
+        let template = app_config
+            .discord
+            .default_channel_name
+            .as_ref()
+            .context(self.strings.channel_name_required)?;
+
+        // プレースホルダーを置換
+        let requester = interaction
+            .member
+            .as_ref()
+            .map(|member| member.display_name().to_string())
+            .unwrap_or_else(|| interaction.user.name.clone());
+        let channel_name = template
+            .replace("{requester}", &requester)
+            .replace("{time}", &Local::now().format("%H:%M").to_string());
+        let channel_name = channel_name.trim();
+
+        // Discordのチャンネル名の制限 (1〜100文字) を確認
+        if channel_name.is_empty() || channel_name.chars().count() > CHANNEL_NAME_MAX_LENGTH {
+            return Err(anyhow!(self.strings.default_channel_name_invalid));
+        }
+
+        Ok(channel_name.to_string())
+    }
+
+    /// コマンドを登録する
+    ///
+    /// サーバーIDを指定した場合はそのサーバーのみにコマンドを登録する
+    pub async fn register_command(&self, http: &Http, guild_id: Option<GuildId>) -> Result<()> {
+        // 名前を変更したり削除したりして使わなくなったコマンドを削除
+        delete_stale_commands(http, guild_id).await?;
+
         // moveコマンドを登録
-        let move_command = Command::create_global_application_command(&ctx, |command| {
+        let move_command = create_command(http, guild_id, |command| {
             command
                 .name("move")
-                .description("みんなでVCを移動する投票ボタンを作成します")
+                .description(self.strings.move_description)
                 .create_option(|option| {
                     option
                         .name("channel_name")
-                        .description("新規作成するチャンネル名")
+                        .description(self.strings.option_channel_name)
                         .kind(CommandOptionType::String)
                         .required(true)
                 })
                 .create_option(|option| {
                     option
                         .name("message")
-                        .description("募集メッセージ")
+                        .description(self.strings.option_message)
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|option| {
+                    option
+                        .name("reason")
+                        .description(self.strings.option_reason)
+                        .kind(CommandOptionType::String)
+                        .max_length(REASON_MAX_LENGTH as u16)
+                })
+                .create_option(|option| {
+                    option
+                        .name("return_after_minutes")
+                        .description(self.strings.option_return_after_minutes)
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                })
+                .create_option(|option| {
+                    option
+                        .name("follow_duration_seconds")
+                        .description(self.strings.option_follow_duration_seconds)
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                        .max_int_value(FOLLOW_DURATION_MAX_SECONDS)
+                })
+                .create_option(|option| {
+                    option
+                        .name("source_channels")
+                        .description(self.strings.option_source_channels)
                         .kind(CommandOptionType::String)
                 })
+                .create_option(|option| {
+                    option
+                        .name("limit_to")
+                        .description(self.strings.option_limit_to)
+                        .kind(CommandOptionType::Mentionable)
+                })
         })
-        .await
-        .context("コマンドの登録に失敗")?;
+        .await?;
 
         // move_toコマンドを登録
-        let move_to_command = Command::create_global_application_command(&ctx, |command| {
+        let move_to_command = create_command(http, guild_id, |command| {
             command
                 .name("move_to")
-                .description("みんなでVCを移動する投票ボタンを作成します")
+                .description(self.strings.move_to_description)
                 .create_option(|option| {
                     option
                         .name("channel")
-                        .description("移動先のチャンネル")
-                        .kind(CommandOptionType::Channel)
-                        .channel_types(&[ChannelType::Voice])
+                        .description(self.strings.option_channel)
+                        .kind(CommandOptionType::String)
+                        .set_autocomplete(true)
                         .required(true)
                 })
                 .create_option(|option| {
                     option
                         .name("message")
-                        .description("募集メッセージ")
+                        .description(self.strings.option_message)
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|option| {
+                    option
+                        .name("reason")
+                        .description(self.strings.option_reason)
                         .kind(CommandOptionType::String)
+                        .max_length(REASON_MAX_LENGTH as u16)
+                })
+                .create_option(|option| {
+                    option
+                        .name("limit_to")
+                        .description(self.strings.option_limit_to)
+                        .kind(CommandOptionType::Mentionable)
                 })
         })
-        .await
-        .context("コマンドの登録に失敗")?;
+        .await?;
 
-        // 登録したコマンドを保存
-        self.move_command_id.lock().await.replace(Commands {
-            move_command: move_command.id,
-            move_to_command: move_to_command.id,
-        });
+        // move_hereコマンドを登録
+        let move_here_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_here")
+                .description(self.strings.move_here_description)
+                .create_option(|option| {
+                    option
+                        .name("message")
+                        .description(self.strings.option_message)
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|option| {
+                    option
+                        .name("reason")
+                        .description(self.strings.option_reason)
+                        .kind(CommandOptionType::String)
+                        .max_length(REASON_MAX_LENGTH as u16)
+                })
+                .create_option(|option| {
+                    option
+                        .name("limit_to")
+                        .description(self.strings.option_limit_to)
+                        .kind(CommandOptionType::Mentionable)
+                })
+        })
+        .await?;
 
-        Ok(())
-    }
+        // move_randomコマンドを登録
+        let move_random_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_random")
+                .description(self.strings.move_random_description)
+                .create_option(|option| {
+                    option
+                        .name("count")
+                        .description(self.strings.option_count)
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(2)
+                        .max_int_value(MOVE_RANDOM_MAX_ROOMS)
+                        .required(true)
+                })
+                .create_option(|option| {
+                    option
+                        .name("message")
+                        .description(self.strings.option_message)
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|option| {
+                    option
+                        .name("reason")
+                        .description(self.strings.option_reason)
+                        .kind(CommandOptionType::String)
+                        .max_length(REASON_MAX_LENGTH as u16)
+                })
+                .create_option(|option| {
+                    option
+                        .name("limit_to")
+                        .description(self.strings.option_limit_to)
+                        .kind(CommandOptionType::Mentionable)
+                })
+        })
+        .await?;
 
-    /// コマンドが呼ばれたときの処理
-    async fn on_move_command(
-        &self,
-        ctx: &Context,
-        interaction: &ApplicationCommandInteraction,
-    ) -> Result<()> {
-        // コマンドを取得
-        let command_id = self
-            .move_command_id
-            .lock()
-            .await
-            .as_ref()
-            .context("コマンドが登録されていません")?
-            .clone();
+        // move_usersコマンドを登録
+        let move_users_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_users")
+                .description(self.strings.move_users_description)
+                .create_option(|option| {
+                    option
+                        .name("channel")
+                        .description(self.strings.option_channel)
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(self.destination_channel_types())
+                        .required(true)
+                })
+                .create_option(|option| {
+                    option
+                        .name("users")
+                        .description(self.strings.option_users)
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+        })
+        .await?;
 
-        // 指定されたチャンネルIDを取得
-        let channel_str: &str = match interaction.data.options[0].value.as_ref() {
-            Some(Value::String(channel)) => channel.as_str(),
-            _ => return Err(anyhow!("チャンネルが指定されていません")),
-        };
-        // 指定されたチャンネルIDを取得
-        let message: String = match interaction
-            .data
-            .options
-            .get(1)
-            .and_then(|option| option.value.as_ref())
-        {
-            Some(Value::String(message)) => format!("\n\n{message}"),
-            _ => "".to_string(),
-        };
+        // dismissコマンドを登録
+        let dismiss_command = create_command(http, guild_id, |command| {
+            command
+                .name("dismiss")
+                .description(self.strings.dismiss_description)
+                .default_member_permissions(Permissions::MOVE_MEMBERS)
+        })
+        .await?;
 
-        // コマンドの種類を取得
-        let command_type = match interaction.data.id {
-            // moveコマンドの場合
-            id if id == command_id.move_command => {
-                // チャンネル名を取得
-                let channel_name = channel_str.to_string();
-                // コマンドの種類を取得
-                CommandType::Move(channel_name)
-            }
-            // move_toコマンドの場合
-            id if id == command_id.move_to_command => {
-                // チャンネルIDを取得
-                let channel_id = ChannelId::from_str(channel_str)
-                    .map_err(|_why| anyhow!("チャンネルが取得できません"))?;
+        // move_selftestコマンドを登録
+        let move_selftest_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_selftest")
+                .description(self.strings.move_selftest_description)
+        })
+        .await?;
 
-                // 権限を確認
-                let channel = channel_id
-                    .to_channel(&ctx)
-                    .await
-                    .context("チャンネルが取得できません")?
-                    .guild()
-                    .context("DMチャンネルは取得できません")?;
-                if !channel
-                    .permissions_for_user(&ctx, interaction.user.id)
-                    .context("権限の取得に失敗")?
-                    .connect()
-                {
-                    return Err(anyhow!("指定されたVCに入る権限がありません"));
-                }
+        // move_retargetコマンドを登録
+        let move_retarget_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_retarget")
+                .description(self.strings.move_retarget_description)
+                .create_option(|option| {
+                    option
+                        .name("channel")
+                        .description(self.strings.option_new_channel)
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(self.destination_channel_types())
+                })
+                .create_option(|option| {
+                    option
+                        .name("channel_name")
+                        .description(self.strings.option_channel_name)
+                        .kind(CommandOptionType::String)
+                })
+        })
+        .await?;
 
-                // コマンドの種類を取得
-                CommandType::MoveTo(channel_id)
+        // cancel_moveコマンドを登録
+        let cancel_move_command = create_command(http, guild_id, |command| {
+            command
+                .name("cancel_move")
+                .description(self.strings.cancel_move_description)
+        })
+        .await?;
+
+        // list_votesコマンドを登録
+        let list_votes_command = create_command(http, guild_id, |command| {
+            command
+                .name("list_votes")
+                .description(self.strings.list_votes_description)
+        })
+        .await?;
+
+        // reload_configコマンドを登録
+        let reload_config_command = create_command(http, guild_id, |command| {
+            command
+                .name("reload_config")
+                .description(self.strings.reload_config_description)
+        })
+        .await?;
+
+        // move_statsコマンドを登録
+        let move_stats_command = create_command(http, guild_id, |command| {
+            command
+                .name("move_stats")
+                .description(self.strings.move_stats_description)
+        })
+        .await?;
+
+        // set_enabledコマンドを登録
+        let set_enabled_command = create_command(http, guild_id, |command| {
+            command
+                .name("set_enabled")
+                .description(self.strings.set_enabled_description)
+                .create_option(|option| {
+                    option
+                        .name("enabled")
+                        .description(self.strings.option_enabled)
+                        .kind(CommandOptionType::Boolean)
+                        .required(true)
+                })
+        })
+        .await?;
+
+        // cleanup_channelsコマンドを登録
+        let cleanup_channels_command = create_command(http, guild_id, |command| {
+            command
+                .name("cleanup_channels")
+                .description(self.strings.cleanup_channels_description)
+                .create_option(|option| {
+                    option
+                        .name("confirm")
+                        .description(self.strings.option_confirm)
+                        .kind(CommandOptionType::Boolean)
+                        .required(true)
+                })
+        })
+        .await?;
+
+        // 登録したコマンドを保存
+        let commands = Commands {
+            move_command: move_command.id,
+            move_to_command: move_to_command.id,
+            move_here_command: move_here_command.id,
+            move_random_command: move_random_command.id,
+            move_users_command: move_users_command.id,
+            dismiss_command: dismiss_command.id,
+            move_selftest_command: move_selftest_command.id,
+            move_retarget_command: move_retarget_command.id,
+            cancel_move_command: cancel_move_command.id,
+            list_votes_command: list_votes_command.id,
+            reload_config_command: reload_config_command.id,
+            move_stats_command: move_stats_command.id,
+            set_enabled_command: set_enabled_command.id,
+            cleanup_channels_command: cleanup_channels_command.id,
+        };
+        warn!("コマンドを登録しました: {:?}", commands);
+        self.move_command_id.lock().await.replace(commands);
+
+        Ok(())
+    }
+
+    /// コマンドが呼ばれたときの処理
+    async fn on_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        // コマンドを取得
+        let command_id = self
+            .move_command_id
+            .lock()
+            .await
+            .as_ref()
+            .context("コマンドが登録されていません")?
+            .clone();
+
+        match interaction.data.id {
+            // move/move_to/move_here/move_randomコマンドの場合
+            id if id == command_id.move_command
+                || id == command_id.move_to_command
+                || id == command_id.move_here_command
+                || id == command_id.move_random_command =>
+            {
+                let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+                let settings = self.guild_config(guild_id)?;
+                if self.misconfigured_guilds.lock().await.contains(&guild_id) {
+                    return Err(anyhow!(self.strings.guild_misconfigured));
+                }
+                self.on_move_command(ctx, interaction, &command_id, &settings)
+                    .await
+            }
+            // move_usersコマンドの場合
+            id if id == command_id.move_users_command => {
+                self.on_move_users_command(ctx, interaction).await
+            }
+            // dismissコマンドの場合
+            id if id == command_id.dismiss_command => {
+                let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+                let settings = self.guild_config(guild_id)?;
+                self.on_dismiss_command(ctx, interaction, &settings).await
+            }
+            // move_selftestコマンドの場合
+            id if id == command_id.move_selftest_command => {
+                self.on_move_selftest_command(ctx, interaction).await
+            }
+            // move_retargetコマンドの場合
+            id if id == command_id.move_retarget_command => {
+                self.on_move_retarget_command(ctx, interaction).await
+            }
+            // cancel_moveコマンドの場合
+            id if id == command_id.cancel_move_command => {
+                self.on_cancel_move_command(ctx, interaction).await
+            }
+            // list_votesコマンドの場合
+            id if id == command_id.list_votes_command => {
+                self.on_list_votes_command(ctx, interaction).await
+            }
+            // reload_configコマンドの場合
+            id if id == command_id.reload_config_command => {
+                self.on_reload_config_command(ctx, interaction).await
+            }
+            // move_statsコマンドの場合
+            id if id == command_id.move_stats_command => {
+                self.on_move_stats_command(ctx, interaction).await
+            }
+            // set_enabledコマンドの場合
+            id if id == command_id.set_enabled_command => {
+                self.on_set_enabled_command(ctx, interaction).await
+            }
+            // cleanup_channelsコマンドの場合
+            id if id == command_id.cleanup_channels_command => {
+                let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+                let settings = self.guild_config(guild_id)?;
+                self.on_cleanup_channels_command(ctx, interaction, &settings)
+                    .await
             }
             // その他の場合は別のBotのコマンドなため、このプログラム内でAcknowledgeしてはいけない
-            _ => return Ok(()),
-        };
+            _ => Ok(()),
+        }
+    }
 
-        // 送信者を取得
+    /// move_usersコマンドが呼ばれたときの処理
+    async fn on_move_users_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        // 送信者がメンバーを移動する権限を持っているか確認
         let member = interaction
             .member
             .as_ref()
-            .ok_or_else(|| anyhow!("送信したユーザーを取得できませんでした"))?;
+            .context(self.strings.sender_not_found)?;
+        if !member
+            .permissions
+            .is_some_and(|permissions| permissions.move_members())
+        {
+            return Err(anyhow!(self.strings.no_move_permission));
+        }
+
+        // 移動先のチャンネルを取得
+        let to_channel_id = match get_option(interaction, "channel") {
+            Some(Value::String(channel)) => ChannelId::from_str(channel)
+                .map_err(|_why| anyhow!(self.strings.channel_not_found))?,
+            _ => return Err(anyhow!(self.strings.channel_not_specified)),
+        };
+        // 移動するユーザーを取得
+        let user_ids = match get_option(interaction, "users") {
+            Some(Value::String(users)) => self
+                .user_mention_regex
+                .captures_iter(users)
+                .filter_map(|caps| caps.get(1)?.as_str().parse::<u64>().ok())
+                .map(UserId)
+                .collect::<Vec<UserId>>(),
+            _ => return Err(anyhow!(self.strings.users_not_specified)),
+        };
+        if user_ids.is_empty() {
+            return Err(anyhow!(self.strings.users_mention_required));
+        }
+
+        // 人数が多いと移動 (一時的な失敗の再試行を含む) に時間がかかるため応答を遅延
+        self.defer_response(ctx, interaction, true).await?;
 
-        // ギルドIDを取得
-        let guild_id = interaction
-            .guild_id
-            .ok_or_else(|| anyhow!("サーバーが見つかりません"))?;
         // ギルドを取得
-        let guild = guild_id
-            .to_guild_cached(&ctx)
-            .ok_or_else(|| anyhow!("サーバーの取得に失敗しました"))?;
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context(self.strings.guild_fetch_failed)?;
 
-        // 送信者がボイスチャンネルにいるか確認
-        let voice_channel_id = guild
+        // ユーザーを1人ずつ移動して結果を記録
+        let mut results = Vec::new();
+        for user_id in user_ids {
+            // ボイスチャンネルにいるか確認
+            let in_voice = guild
+                .voice_states
+                .get(&user_id)
+                .and_then(|voice_state| voice_state.channel_id)
+                .is_some();
+            let result = if !in_voice {
+                format!("❌ {}", self.strings.not_in_voice)
+            } else {
+                let retries = self.app_config().discord.move_retry_attempts;
+                match retry_with_backoff(retries, || {
+                    self.discord
+                        .move_member(&ctx.http, guild_id, user_id, to_channel_id)
+                })
+                .await
+                {
+                    Ok(_) => self.strings.move_users_moved.to_string(),
+                    Err(why) => {
+                        warn!("メンバーの移動に失敗: {:?}", why);
+                        self.strings.move_users_failed.to_string()
+                    }
+                }
+            };
+            results.push(format!("{}: {result}", user_id.mention()));
+        }
+
+        // 結果を返信
+        interaction
+            .edit_original_interaction_response(&ctx, |response| {
+                response.content(
+                    self.strings
+                        .move_users_result
+                        .format(&[to_channel_id.mention().to_string(), results.join("\n")]),
+                )
+            })
+            .await
+            .context("移動した結果の送信に失敗")?;
+
+        Ok(())
+    }
+
+    /// 時間がかかるコマンドの応答を遅延する
+    ///
+    /// 遅延したあとのエラーは遅延した応答を編集して返信するため、遅延したことを記録する
+    async fn defer_response(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        ephemeral: bool,
+    ) -> Result<()> {
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|message| message.ephemeral(ephemeral))
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+        self.deferred_interactions
+            .lock()
+            .await
+            .insert(interaction.id);
+        Ok(())
+    }
+
+    /// 許可されたロールを持っているか確認 (設定されていなければ全員に許可)
+    fn check_allowed_role(&self, interaction: &ApplicationCommandInteraction) -> Result<()> {
+        let allowed_role_ids = &self.app_config().discord.allowed_role_ids;
+        if !allowed_role_ids.is_empty()
+            && !interaction.member.as_ref().is_some_and(|member| {
+                member
+                    .roles
+                    .iter()
+                    .any(|role_id| allowed_role_ids.contains(role_id))
+            })
+        {
+            return Err(anyhow!(self.strings.no_permission));
+        }
+        Ok(())
+    }
+
+    /// dismissコマンドが呼ばれたときの処理
+    ///
+    /// 投票せずに、主催者がいるVCのBot以外の全員をロビーのVCへ移動する
+    async fn on_dismiss_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        settings: &GuildSettings,
+    ) -> Result<()> {
+        // 許可されたロールを持っているか確認
+        self.check_allowed_role(interaction)?;
+
+        // VCにいる全員を移動するため、送信者がメンバーを移動する権限を持っているか確認
+        let member = interaction
+            .member
+            .as_ref()
+            .context(self.strings.sender_not_found)?;
+        if !member
+            .permissions
+            .is_some_and(|permissions| permissions.move_members())
+        {
+            return Err(anyhow!(self.strings.no_move_permission));
+        }
+
+        // ロビーのVCを取得
+        let lobby_channel_id = settings
+            .lobby_channel
+            .context(self.strings.lobby_not_configured)?;
+
+        // 主催者がいるVCを取得
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context(self.strings.guild_fetch_failed)?;
+        let source_channel_id = guild
             .voice_states
-            .get(&member.user.id)
+            .get(&interaction.user.id)
             .and_then(|voice_state| voice_state.channel_id)
-            .ok_or_else(|| anyhow!("ボイスチャンネルに参加していません"))?;
+            .context(self.strings.not_in_voice)?;
+        if settings.vc_ignored_channels.contains(&source_channel_id) {
+            return Err(anyhow!(self.strings.ignored_channel));
+        }
+        if source_channel_id == lobby_channel_id {
+            return Err(anyhow!(self.strings.already_in_lobby));
+        }
+
+        // VCにいるBot以外のメンバーを取得 (キャッシュにないメンバーはBotでないとみなす)
+        let user_ids = guild
+            .voice_states
+            .values()
+            .filter(|voice_state| voice_state.channel_id == Some(source_channel_id))
+            .filter(|voice_state| {
+                !guild
+                    .members
+                    .get(&voice_state.user_id)
+                    .is_some_and(|member| member.user.bot)
+            })
+            .map(|voice_state| voice_state.user_id)
+            .collect::<Vec<UserId>>();
+
+        // 人数が多いと移動 (一時的な失敗の再試行を含む) に時間がかかるため応答を遅延
+        self.defer_response(ctx, interaction, true).await?;
+
+        // 同時に実行する数を制限して移動し、ユーザーごとの結果を記録
+        let retries = self.app_config().discord.move_retry_attempts;
+        let results = stream::iter(user_ids)
+            .map(|user_id| async move {
+                let result = match retry_with_backoff(retries, || {
+                    self.discord
+                        .move_member(&ctx.http, guild_id, user_id, lobby_channel_id)
+                })
+                .await
+                {
+                    Ok(_) => self.strings.move_users_moved,
+                    Err(why) => {
+                        warn!("メンバーの移動に失敗: {} {:?}", user_id, why);
+                        self.strings.move_users_failed
+                    }
+                };
+                format!("{}: {result}", user_id.mention())
+            })
+            .buffered(self.app_config().discord.max_concurrent_moves.max(1))
+            .collect::<Vec<String>>()
+            .await;
+
+        // 結果を返信 (人数が多くてもメッセージの長さの制限を超えないようにする)
+        let content = self
+            .strings
+            .move_users_result
+            .format(&[lobby_channel_id.mention().to_string(), results.join("\n")]);
+        interaction
+            .edit_original_interaction_response(&ctx, |response| {
+                response.content(truncate_with_ellipsis(content, MESSAGE_CONTENT_MAX_LENGTH))
+            })
+            .await
+            .context("移動した結果の送信に失敗")?;
+
+        Ok(())
+    }
+
+    /// move_selftestコマンドが呼ばれたときの処理
+    async fn on_move_selftest_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        // Botのオーナーか確認
+        if !is_owner(ctx, interaction.user.id).await? {
+            return Err(anyhow!(self.strings.owner_only));
+        }
+
+        // 動作確認用のカテゴリを取得
+        let category_id = self
+            .app_config()
+            .discord
+            .selftest_category
+            .context(self.strings.selftest_category_not_configured)?;
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+
+        // 時間がかかるため応答を遅延
+        self.defer_response(ctx, interaction, true).await?;
+
+        // 各手順を実行して結果を記録
+        let mut results = Vec::new();
+        let mut channels = Vec::new();
+        for name in ["selftest-1", "selftest-2"] {
+            match guild_id
+                .create_channel(&ctx, |c| {
+                    c.name(name).kind(ChannelType::Voice).category(category_id)
+                })
+                .await
+            {
+                Ok(channel) => {
+                    results.push(format!(
+                        "✅ {}",
+                        self.strings.selftest_create_channel.format(&[name])
+                    ));
+                    channels.push(channel);
+                }
+                Err(why) => results.push(format!(
+                    "❌ {}: {why}",
+                    self.strings.selftest_create_channel.format(&[name])
+                )),
+            }
+        }
+        if let Some(channel) = channels.first_mut() {
+            match channel.edit(&ctx, |c| c.name("selftest-renamed")).await {
+                Ok(_) => results.push(format!("✅ {}", self.strings.selftest_rename_channel)),
+                Err(why) => results.push(format!(
+                    "❌ {}: {why}",
+                    self.strings.selftest_rename_channel
+                )),
+            }
+        }
+        for channel in &channels {
+            let step = self
+                .strings
+                .selftest_move_permission
+                .format(&[&channel.name]);
+            let can_move = channel
+                .permissions_for_user(ctx, ctx.cache.current_user_id())
+                .map(|permissions| permissions.move_members() && permissions.connect());
+            match can_move {
+                Ok(true) => results.push(format!("✅ {step}")),
+                Ok(false) => results.push(format!("❌ {step}")),
+                Err(why) => results.push(format!("❌ {step}: {why}")),
+            }
+        }
+        for channel in &channels {
+            let step = self
+                .strings
+                .selftest_delete_channel
+                .format(&[&channel.name]);
+            match channel.delete(&ctx).await {
+                Ok(_) => results.push(format!("✅ {step}")),
+                Err(why) => results.push(format!("❌ {step}: {why}")),
+            }
+        }
+
+        // 結果を返信
+        interaction
+            .edit_original_interaction_response(&ctx, |response| {
+                response.content(self.strings.selftest_result.format(&[results.join("\n")]))
+            })
+            .await
+            .context("動作確認の結果の送信に失敗")?;
+
+        Ok(())
+    }
+
+    /// move_retargetコマンドが呼ばれたときの処理
+    async fn on_move_retarget_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+
+        // 新しい移動先を取得
+        let command_type = match (
+            get_option(interaction, "channel"),
+            get_option(interaction, "channel_name"),
+        ) {
+            (Some(Value::String(channel)), _) => {
+                let channel_id = ChannelId::from_str(channel)
+                    .map_err(|_why| anyhow!(self.strings.channel_not_found))?;
+                // 権限を確認
+                check_connect_permission(
+                    &*self.discord,
+                    ctx,
+                    channel_id,
+                    interaction.user.id,
+                    self.destination_channel_types(),
+                    self.strings,
+                )
+                .await?;
+                self.check_move_to_category(ctx, channel_id).await?;
+                CommandType::MoveTo(channel_id)
+            }
+            (_, Some(Value::String(channel_name))) if !channel_name.trim().is_empty() => {
+                // 前後の空白を除き、チャンネル名が許可されているか確認
+                let channel_name = channel_name.trim();
+                self.validate_channel_name(channel_name)?;
+                CommandType::Move(channel_name.to_string())
+            }
+            _ => return Err(anyhow!(self.strings.retarget_target_required)),
+        };
+
+        // 募集を開始した人の進行中の投票の移動先を変更
+        let (message_id, vote) = {
+            let mut votes = self.votes.lock().await;
+            let (message_id, vote) = votes
+                .iter_mut()
+                .filter(|(_, vote)| {
+                    vote.guild_id == guild_id && vote.requester == interaction.user.id
+                })
+                .max_by_key(|(_, vote)| vote.created_at)
+                .context(self.strings.no_active_vote)?;
+            vote.command_type = command_type;
+            (*message_id, vote.clone())
+        };
+        self.save_votes().await;
+
+        // 募集メッセージを編集
+        self.edit_vote_message(ctx, message_id, &vote).await?;
+
+        // 返信をする
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true);
+                        message.content(
+                            self.strings
+                                .retargeted
+                                .format(&[vote.command_type.to_text(self.strings)]),
+                        );
+                        message
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        Ok(())
+    }
+
+    /// cancel_moveコマンドが呼ばれたときの処理
+    async fn on_cancel_move_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+
+        // 募集を開始した人の進行中の投票を取得
+        let (message_id, vote) = {
+            let mut votes = self.votes.lock().await;
+            let message_id = votes
+                .iter()
+                .filter(|(_, vote)| {
+                    vote.guild_id == guild_id && vote.requester == interaction.user.id
+                })
+                .max_by_key(|(_, vote)| vote.created_at)
+                .map(|(message_id, _)| *message_id);
+            match message_id.and_then(|message_id| votes.remove_entry(&message_id)) {
+                Some(entry) => entry,
+                None => {
+                    // このチャンネルに他の人が開始した募集があれば権限のエラー
+                    if votes
+                        .values()
+                        .any(|vote| vote.channel_id == interaction.channel_id)
+                    {
+                        return Err(anyhow!(self.strings.cancel_not_organizer));
+                    }
+                    return Err(anyhow!(self.strings.no_active_vote));
+                }
+            }
+        };
+        self.save_votes().await;
+
+        // 時間切れで削除する処理を中止
+        if let Some(timer) = self.vote_timers.lock().await.remove(&message_id) {
+            timer.abort();
+        }
+
+        // 募集メッセージを削除
+        let message = vote
+            .channel_id
+            .message(&ctx, message_id)
+            .await
+            .context("募集メッセージの取得に失敗")?;
+        delete_vote_message(&*self.discord, ctx, vote.webhook.as_ref(), &message)
+            .await
+            .context("募集メッセージの削除に失敗")?;
+
+        // 返信
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true);
+                        message.content(self.strings.cancelled);
+                        message
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        Ok(())
+    }
+
+    /// list_votesコマンドが呼ばれたときの処理
+    async fn on_list_votes_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+
+        // このサーバーの進行中の投票を古い順に取得
+        let mut votes = self
+            .votes
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, vote)| vote.guild_id == guild_id)
+            .map(|(message_id, vote)| (*message_id, vote.clone()))
+            .collect::<Vec<(MessageId, Vote)>>();
+        votes.sort_by_key(|(_, vote)| vote.created_at);
+        let lines = votes
+            .iter()
+            .map(|(message_id, vote)| {
+                // 残り時間 (1分以上は分に切り上げ、1分未満は秒)
+                let remaining = vote.timeout.saturating_sub(vote.created_at.elapsed());
+                let remaining = match remaining.as_secs() {
+                    seconds if seconds >= 60 => Duration::from_secs(seconds.div_ceil(60) * 60),
+                    _ => remaining,
+                };
+                self.strings.list_votes_entry.format(&[
+                    message_id.link(vote.channel_id, Some(guild_id)).to_string(),
+                    vote.requester.mention().to_string(),
+                    vote.command_type.to_text(self.strings),
+                    vote.participant_count().to_string(),
+                    duration_text(remaining, self.strings),
+                ])
+            })
+            .collect::<Vec<String>>();
+
+        // 返信
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true);
+                        if lines.is_empty() {
+                            message.content(self.strings.no_active_votes);
+                        } else {
+                            message.embed(|embed| {
+                                embed.title(self.strings.list_votes_title);
+                                embed.description(lines.join("\n\n"));
+                                embed
+                            });
+                        }
+                        message
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        Ok(())
+    }
+
+    /// move_statsコマンドが呼ばれたときの処理
+    async fn on_move_stats_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+
+        // 期間ごとの移動の回数と人数
+        let totals = MOVE_STATS_PERIODS
+            .iter()
+            .map(|days| Ok((*days, self.move_history.totals(guild_id, *days)?)))
+            .collect::<Result<Vec<_>>>()?;
+        // 最も長い期間でよく募集する人
+        let longest_period = MOVE_STATS_PERIODS.iter().max().copied().unwrap_or(30);
+        let top_organizers = self
+            .move_history
+            .top_organizers(guild_id, longest_period, MOVE_STATS_TOP_ORGANIZERS)?
+            .iter()
+            .enumerate()
+            .map(|(index, (organizer_id, moves))| {
+                self.strings.move_stats_organizer_entry.format(&[
+                    (index + 1).to_string(),
+                    organizer_id.mention().to_string(),
+                    moves.to_string(),
+                ])
+            })
+            .collect::<Vec<String>>();
+
+        // 返信
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true);
+                        message.embed(|embed| {
+                            embed.title(self.strings.move_stats_title);
+                            for (days, totals) in &totals {
+                                embed.field(
+                                    self.strings.move_stats_period.format(&[days]),
+                                    self.strings
+                                        .move_stats_totals
+                                        .format(&[totals.moves, totals.members]),
+                                    true,
+                                );
+                            }
+                            embed.field(
+                                self.strings
+                                    .move_stats_top_organizers
+                                    .format(&[longest_period]),
+                                if top_organizers.is_empty() {
+                                    self.strings.move_stats_empty.to_string()
+                                } else {
+                                    top_organizers.join("\n")
+                                },
+                                false,
+                            );
+                            embed
+                        });
+                        message
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        Ok(())
+    }
+
+    /// reload_configコマンドが呼ばれたときの処理
+    async fn on_reload_config_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        // Botのオーナーか確認
+        if !is_owner(ctx, interaction.user.id).await? {
+            return Err(anyhow!(self.strings.owner_only));
+        }
+
+        // 設定ファイルを読み込み、起動時と同じ確認をする
+        let new_config = AppConfig::load_config(&self.basedir)
+            .and_then(|app_config| {
+                parse_vote_format(&app_config)?;
+                result_ack_emoji(&app_config)?;
+                channel_name_pattern(&app_config)?;
+                validate_message_templates(&app_config)?;
+                app_config.validate_intents()?;
+                Ok(app_config)
+            })
+            .map_err(|why| {
+                anyhow!(self
+                    .strings
+                    .config_reload_failed
+                    .format(&[format!("{:#}", why)]))
+            })?;
+
+        // 変更された設定を確認して置き換える
+        let changes = self.app_config().changes(&new_config)?;
+        *self
+            .app_config
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(new_config);
+        warn!("設定を再読み込みしました: {:?}", changes);
+
+        // 変更された設定を1行ずつ表示 (埋め込みの説明の長さの制限を超える場合は省略)
+        let lines = changes
+            .iter()
+            .map(|(key, old, new)| {
+                let note = if RESTART_REQUIRED_CONFIG.contains(&key.as_str()) {
+                    self.strings.config_restart_required
+                } else {
+                    ""
+                };
+                format!("`{key}`: {old} → {new}{note}")
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let lines = truncate_with_ellipsis(lines, EMBED_DESCRIPTION_MAX_LENGTH);
+
+        // 返信
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true);
+                        if changes.is_empty() {
+                            message.content(self.strings.config_unchanged);
+                        } else {
+                            message.embed(|embed| {
+                                embed.title(self.strings.config_reloaded);
+                                embed.description(lines);
+                                embed
+                            });
+                        }
+                        message
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        // 新しい設定のチャンネルとカテゴリを確認
+        self.validate_guild_configs(ctx, &ctx.cache.guilds()).await;
+
+        Ok(())
+    }
+
+    /// set_enabledコマンドが呼ばれたときの処理
+    ///
+    /// 新しい募集の受け付けのみを切り替え、進行中の募集はそのまま締め切れるようにする
+    async fn on_set_enabled_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+    ) -> Result<()> {
+        // Botのオーナーか確認
+        if !is_owner(ctx, interaction.user.id).await? {
+            return Err(anyhow!(self.strings.owner_only));
+        }
+
+        let enabled = match get_option(interaction, "enabled") {
+            Some(Value::Bool(enabled)) => *enabled,
+            _ => return Err(anyhow!(self.strings.enabled_not_specified)),
+        };
+        self.enabled.store(enabled, Ordering::Relaxed);
+        warn!(
+            "新しい募集の受け付けを{}しました (実行したユーザー: {})",
+            if enabled { "再開" } else { "停止" },
+            interaction.user.id
+        );
+
+        // 返信
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|message| {
+                        message.ephemeral(true).content(if enabled {
+                            self.strings.votes_enabled
+                        } else {
+                            self.strings.votes_disabled
+                        })
+                    })
+            })
+            .await
+            .map_err(|_why| anyhow!(self.strings.response_failed))?;
+
+        Ok(())
+    }
+
+    /// cleanup_channelsコマンドが呼ばれたときの処理
+    ///
+    /// 設定したカテゴリの中の誰もいないVCを削除する (VC作成チャンネル、除外対象のチャンネル、ロビーのVCは残す)
+    async fn on_cleanup_channels_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        settings: &GuildSettings,
+    ) -> Result<()> {
+        // Botのオーナーか確認
+        if !is_owner(ctx, interaction.user.id).await? {
+            return Err(anyhow!(self.strings.owner_only));
+        }
+
+        let confirm = match get_option(interaction, "confirm") {
+            Some(Value::Bool(confirm)) => *confirm,
+            _ => false,
+        };
+
+        // カテゴリの中の誰もいないVCを取得
+        let guild_id = interaction.guild_id.context(self.strings.guild_not_found)?;
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context(self.strings.guild_fetch_failed)?;
+        let empty_channels = guild
+            .channels
+            .values()
+            .filter_map(|channel| match channel {
+                Channel::Guild(channel) => Some(channel),
+                _ => None,
+            })
+            .filter(|channel| {
+                channel.parent_id == Some(settings.vc_category)
+                    && matches!(channel.kind, ChannelType::Voice | ChannelType::Stage)
+                    && !settings.is_create_channel(channel.id)
+                    && !settings.vc_ignored_channels.contains(&channel.id)
+                    && settings.lobby_channel != Some(channel.id)
+                    && !guild
+                        .voice_states
+                        .values()
+                        .any(|voice_state| voice_state.channel_id == Some(channel.id))
+            })
+            .map(|channel| channel.id)
+            .collect::<Vec<ChannelId>>();
+
+        // 確認のため、confirmを指定しなければ削除するVCを表示するだけにする
+        if !confirm {
+            // メッセージの長さの制限を超えないよう、表示するVCの数を制限する
+            let mut channel_list = empty_channels
+                .iter()
+                .take(CLEANUP_PREVIEW_MAX_CHANNELS)
+                .map(|channel_id| channel_id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            if empty_channels.len() > CLEANUP_PREVIEW_MAX_CHANNELS {
+                channel_list += " …";
+            }
+            let content = self
+                .strings
+                .cleanup_channels_preview
+                .format(&[empty_channels.len().to_string(), channel_list]);
+            interaction
+                .create_interaction_response(&ctx, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|message| {
+                            message.ephemeral(true).content(content)
+                        })
+                })
+                .await
+                .map_err(|_why| anyhow!(self.strings.response_failed))?;
+            return Ok(());
+        }
+
+        // 時間がかかるため応答を遅延
+        self.defer_response(ctx, interaction, true).await?;
+
+        // 1つずつ削除して結果を記録
+        let mut deleted = 0;
+        let mut failed = 0;
+        for channel_id in &empty_channels {
+            match self.discord.delete_channel(&ctx.http, *channel_id).await {
+                Ok(_) => {
+                    deleted += 1;
+                    self.created_channels.lock().await.remove(channel_id);
+                    self.forget_created_channel(guild_id, *channel_id).await;
+                }
+                Err(why) => {
+                    failed += 1;
+                    warn!("誰もいないVCの削除に失敗: {} {:?}", channel_id, why);
+                }
+            }
+        }
+        warn!(
+            "誰もいないVCを削除しました: {}個 (失敗: {}個, 実行したユーザー: {})",
+            deleted, failed, interaction.user.id
+        );
+
+        // 結果を返信
+        interaction
+            .edit_original_interaction_response(&ctx, |response| {
+                response.content(
+                    self.strings
+                        .cleanup_channels_done
+                        .format(&[deleted, failed]),
+                )
+            })
+            .await
+            .context("削除した結果の送信に失敗")?;
+
+        Ok(())
+    }
+
+    /// 設定したVC作成チャンネルやカテゴリが正しいか確認し、誤りがあるサーバーを記録する
+    async fn validate_guild_configs(&self, ctx: &Context, guild_ids: &[GuildId]) {
+        let app_config = self.app_config();
+        let mut misconfigured_guilds = HashSet::new();
+        for &guild_id in guild_ids {
+            // 設定されていないサーバーは確認しない
+            let settings = match app_config.guild(guild_id) {
+                Some(settings) => settings,
+                None => continue,
+            };
+            let problems = guild_config_problems(ctx, guild_id, &settings).await;
+            for problem in &problems {
+                warn!("サーバー {} の設定に誤りがあります: {}", guild_id, problem);
+            }
+            if !problems.is_empty() {
+                misconfigured_guilds.insert(guild_id);
+            }
+        }
+        *self.misconfigured_guilds.lock().await = misconfigured_guilds;
+    }
+
+    /// サーバーで使う設定を取得 (設定されていないサーバーではエラー)
+    fn guild_config(&self, guild_id: GuildId) -> Result<GuildSettings> {
+        self.app_config()
+            .guild(guild_id)
+            .context(self.strings.guild_not_configured)
+    }
+
+    /// 移動先に指定できるチャンネルの種類 (設定によりステージチャンネルを含める)
+    fn destination_channel_types(&self) -> &'static [ChannelType] {
+        if self.app_config().discord.allow_stage_channels {
+            &[ChannelType::Voice, ChannelType::Stage]
+        } else {
+            &[ChannelType::Voice]
+        }
+    }
+
+    /// move_toの移動先に指定できるカテゴリの中のチャンネルか確認する (設定がない場合はすべて許可)
+    async fn check_move_to_category(&self, ctx: &Context, channel_id: ChannelId) -> Result<()> {
+        let allowed_categories = &self.app_config().discord.move_to_allowed_categories;
+        if allowed_categories.is_empty() {
+            return Ok(());
+        }
+
+        let channel = self
+            .discord
+            .channel(&ctx.cache, &ctx.http, channel_id)
+            .await
+            .context(self.strings.channel_not_found)?
+            .guild()
+            .context("DMチャンネルは取得できません")?;
+        if !channel
+            .parent_id
+            .is_some_and(|parent_id| allowed_categories.contains(&parent_id))
+        {
+            let categories = allowed_categories
+                .iter()
+                .map(|category| category.mention().to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            return Err(anyhow!(self
+                .strings
+                .category_not_allowed
+                .format(&[categories])));
+        }
+        Ok(())
+    }
+
+    /// 一緒に集めるVCの指定 (チャンネルのメンションまたはIDを空白やカンマで区切ったもの) を読み取る
+    ///
+    /// 主催者がいるVCや重複したVCは除き、ボイスチャンネル以外や除外対象のチャンネルが含まれていればエラー
+    fn parse_source_channels(
+        &self,
+        guild: &Guild,
+        settings: &GuildSettings,
+        input: &str,
+        voice_channel_id: ChannelId,
+    ) -> Result<Vec<ChannelId>> {
+        let mut channel_ids = Vec::new();
+        for token in input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+        {
+            let channel_id = token
+                .trim_start_matches("<#")
+                .trim_end_matches('>')
+                .parse::<u64>()
+                .map(ChannelId)
+                .map_err(|_why| anyhow!(self.strings.channel_not_found))?;
+            let is_voice = matches!(
+                guild.channels.get(&channel_id),
+                Some(Channel::Guild(channel))
+                    if matches!(channel.kind, ChannelType::Voice | ChannelType::Stage)
+            );
+            if !is_voice {
+                return Err(anyhow!(self.strings.source_channel_not_voice));
+            }
+            if settings.vc_ignored_channels.contains(&channel_id) {
+                return Err(anyhow!(self.strings.ignored_channel));
+            }
+            if channel_id != voice_channel_id && !channel_ids.contains(&channel_id) {
+                channel_ids.push(channel_id);
+            }
+        }
+        Ok(channel_ids)
+    }
+
+    /// 入力中の文字列を含むボイスチャンネルを取得 (設定したカテゴリの中のチャンネルを優先)
+    fn voice_channel_candidates(
+        &self,
+        guild: &Guild,
+        settings: &GuildSettings,
+        input: &str,
+    ) -> Vec<GuildChannel> {
+        let input = input.trim().to_lowercase();
+        let channel_types = self.destination_channel_types();
+        let app_config = self.app_config();
+        let allowed_categories = &app_config.discord.move_to_allowed_categories;
+        let mut channels = guild
+            .channels
+            .values()
+            .filter_map(|channel| channel.clone().guild())
+            .filter(|channel| channel_types.contains(&channel.kind))
+            // VC作成チャンネルや除外対象のチャンネルは候補にしない
+            .filter(|channel| {
+                !settings.is_create_channel(channel.id)
+                    && !settings.vc_ignored_channels.contains(&channel.id)
+            })
+            // 移動先に指定できるカテゴリが設定されていれば、その中のチャンネルのみ候補にする
+            .filter(|channel| {
+                allowed_categories.is_empty()
+                    || channel
+                        .parent_id
+                        .is_some_and(|parent_id| allowed_categories.contains(&parent_id))
+            })
+            .filter(|channel| channel.name.to_lowercase().contains(&input))
+            .collect::<Vec<GuildChannel>>();
+        channels.sort_by_key(|channel| {
+            (
+                channel.parent_id != Some(settings.vc_category),
+                channel.position,
+            )
+        });
+        channels
+    }
+
+    /// コマンドの入力中に候補を返す
+    async fn on_autocomplete(
+        &self,
+        ctx: &Context,
+        interaction: &AutocompleteInteraction,
+    ) -> Result<()> {
+        // move_toコマンドのchannelオプションのみ候補を返す
+        let is_move_to = self
+            .move_command_id
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|commands| commands.move_to_command == interaction.data.id);
+        if !is_move_to {
+            return Ok(());
+        }
+        let input = match interaction
+            .data
+            .options
+            .iter()
+            .find(|option| option.focused && option.name == "channel")
+        {
+            Some(option) => option
+                .value
+                .as_ref()
+                .and_then(|value| value.as_str())
+                .unwrap_or_default(),
+            None => return Ok(()),
+        };
+
+        // サーバーのボイスチャンネルから候補を取得 (最大25件)
+        let guild = interaction
+            .guild_id
+            .and_then(|guild_id| self.discord.guild(&ctx.cache, guild_id))
+            .context(self.strings.guild_not_found)?;
+        let settings = self.guild_config(guild.id)?;
+        let candidates = self.voice_channel_candidates(&guild, &settings, input);
+
+        interaction
+            .create_autocomplete_response(&ctx, |response| {
+                for channel in candidates.iter().take(25) {
+                    response.add_string_choice(&channel.name, channel.id);
+                }
+                response
+            })
+            .await
+            .context("候補の送信に失敗")?;
+
+        Ok(())
+    }
+
+    /// move/move_to/move_hereコマンドが呼ばれたときの処理
+    async fn on_move_command(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        command_id: &Commands,
+        settings: &GuildSettings,
+    ) -> Result<()> {
+        // 新しい募集の受け付けを停止していれば何もしない
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Err(anyhow!(self.strings.maintenance));
+        }
+
+        // 許可されたロールを持っているか確認
+        self.check_allowed_role(interaction)?;
+
+        // ギルドIDを取得
+        let guild_id = interaction
+            .guild_id
+            .ok_or_else(|| anyhow!(self.strings.guild_not_found))?;
+
+        // 募集を投稿する前に、Botがこのチャンネルで必要な権限を持っているか確認
+        check_bot_permissions(
+            ctx,
+            interaction.channel_id,
+            required_permissions(&self.app_config()),
+            self.strings,
+        )
+        .await?;
+
+        // 前回募集を開始してからクールダウン中か確認
+        let cooldown = Duration::from_secs(self.app_config().discord.move_cooldown_seconds);
+        if let Some(last) = self
+            .move_cooldowns
+            .lock()
+            .await
+            .get(&(guild_id, interaction.user.id))
+        {
+            let remaining = cooldown.saturating_sub(last.elapsed());
+            if !remaining.is_zero() {
+                return Err(anyhow!(self
+                    .strings
+                    .cooldown
+                    .format(&[remaining.as_secs().max(1)])));
+            }
+        }
+
+        // 指定されたチャンネルIDを取得 (move_hereとmove_randomでは指定しない)
+        let channel_str: &str =
+            match get_option(interaction, "channel_name").or(get_option(interaction, "channel")) {
+                Some(Value::String(channel)) => channel.as_str(),
+                _ if interaction.data.id == command_id.move_here_command
+                    || interaction.data.id == command_id.move_random_command =>
+                {
+                    ""
+                }
+                _ => return Err(anyhow!(self.strings.channel_not_specified)),
+            };
+        // 募集メッセージを取得
+        let message: String = match get_option(interaction, "message") {
+            Some(Value::String(message)) => format!("\n\n{message}"),
+            _ => "".to_string(),
+        };
+        // 移動の理由を取得
+        let reason = match get_option(interaction, "reason") {
+            Some(Value::String(reason)) => sanitize_reason(reason),
+            _ => None,
+        };
+        // 元のVCへ戻るまでの時間を取得
+        let return_after_minutes = get_option(interaction, "return_after_minutes")
+            .and_then(|value| value.as_u64())
+            .filter(|minutes| *minutes > 0);
+        // 移動後もあとから参加した人を移動する時間を取得
+        let follow_duration = get_option(interaction, "follow_duration_seconds")
+            .and_then(|value| value.as_u64())
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| Duration::from_secs(seconds.min(FOLLOW_DURATION_MAX_SECONDS)));
+        // 移動できるメンバーの制限を取得 (ロールとして解決できればロール、それ以外はユーザー)
+        let limit_to = match get_option(interaction, "limit_to") {
+            Some(Value::String(id)) => id.parse::<u64>().ok().map(|id| {
+                if interaction.data.resolved.roles.contains_key(&RoleId(id)) {
+                    LimitTo::Role(RoleId(id))
+                } else {
+                    LimitTo::User(UserId(id))
+                }
+            }),
+            _ => None,
+        };
+
+        // コマンドの種類を取得
+        let command_type = match interaction.data.id {
+            // moveコマンドの場合
+            id if id == command_id.move_command => {
+                // チャンネル名を取得 (空の場合は設定されたデフォルトのチャンネル名を使用)
+                let channel_name = if channel_str.trim().is_empty() {
+                    self.default_channel_name(interaction)?
+                } else {
+                    channel_str.trim().to_string()
+                };
+                // チャンネル名が許可されているか確認
+                self.validate_channel_name(&channel_name)?;
+                // Botが作成したVCの数が上限に達していれば募集しない
+                if self.app_config().discord.create_channel_explicitly {
+                    self.check_created_channel_limit(guild_id).await?;
+                }
+                // コマンドの種類を取得
+                CommandType::Move(channel_name)
+            }
+            // move_toコマンドの場合
+            id if id == command_id.move_to_command => {
+                // チャンネルIDを取得 (候補を選ばずに入力した場合はチャンネル名で探す)
+                let channel_id = match ChannelId::from_str(channel_str) {
+                    Ok(channel_id) => channel_id,
+                    Err(_) => interaction
+                        .guild_id
+                        .and_then(|guild_id| self.discord.guild(&ctx.cache, guild_id))
+                        .and_then(|guild| {
+                            self.voice_channel_candidates(&guild, settings, channel_str)
+                                .into_iter()
+                                .find(|channel| channel.name == channel_str.trim())
+                        })
+                        .map(|channel| channel.id)
+                        .context(self.strings.channel_not_found)?,
+                };
+
+                // 権限を確認
+                check_connect_permission(
+                    &*self.discord,
+                    ctx,
+                    channel_id,
+                    interaction.user.id,
+                    self.destination_channel_types(),
+                    self.strings,
+                )
+                .await?;
+                self.check_move_to_category(ctx, channel_id).await?;
+
+                // コマンドの種類を取得
+                CommandType::MoveTo(channel_id)
+            }
+            // move_hereコマンドの場合
+            id if id == command_id.move_here_command => {
+                // 主催者がいるVCを移動先にする (VCの作成や名前の変更はしない)
+                let channel_id = cached_guild(&*self.discord, ctx, guild_id)
+                    .await
+                    .and_then(|guild| {
+                        guild
+                            .voice_states
+                            .get(&interaction.user.id)
+                            .and_then(|voice_state| voice_state.channel_id)
+                    })
+                    .context(self.strings.move_here_not_in_voice)?;
+
+                // コマンドの種類を取得
+                CommandType::MoveHere(channel_id)
+            }
+            // move_randomコマンドの場合
+            id if id == command_id.move_random_command => {
+                // 分けるVCの数を取得
+                let count = get_option(interaction, "count")
+                    .and_then(|value| value.as_u64())
+                    .context(self.strings.channel_not_specified)?
+                    .clamp(2, MOVE_RANDOM_MAX_ROOMS);
+
+                // コマンドの種類を取得
+                CommandType::MoveRandom(count as usize)
+            }
+            // その他の場合は別のBotのコマンドなため、このプログラム内でAcknowledgeしてはいけない
+            _ => return Ok(()),
+        };
+
+        // 送信者を取得
+        let member = interaction
+            .member
+            .as_ref()
+            .ok_or_else(|| anyhow!(self.strings.sender_not_found))?;
+
+        // ギルドを取得
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .ok_or_else(|| anyhow!(self.strings.guild_fetch_failed))?;
+
+        // 送信者がボイスチャンネルにいるか確認
+        let voice_channel_id = guild
+            .voice_states
+            .get(&member.user.id)
+            .and_then(|voice_state| voice_state.channel_id)
+            .ok_or_else(|| anyhow!(self.strings.not_in_voice))?;
+
+        // 一緒に集めるVCを取得 (主催者がいるVCに加えて、指定したVCのメンバーにも募集する)
+        let additional_source_channel_ids = match get_option(interaction, "source_channels") {
+            Some(Value::String(channels)) => {
+                self.parse_source_channels(&guild, settings, channels, voice_channel_id)?
+            }
+            _ => vec![],
+        };
+        let source_channels = std::iter::once(voice_channel_id)
+            .chain(additional_source_channel_ids.iter().copied())
+            .collect::<Vec<ChannelId>>();
+
+        // 重複して募集していないか確認し、募集を開始し終えるまで同じチャンネルとVCを予約する
+        let _reservation = self
+            .reserve_vote(
+                guild_id,
+                interaction.id,
+                interaction.channel_id,
+                &source_channels,
+            )
+            .await?;
+
+        // VCのメンバーを取得 (一緒に集めるVCのメンバーを含む)
+        let voice_member_mentions = guild
+            .voice_states
+            .iter()
+            .filter(|(_, state)| {
+                state
+                    .channel_id
+                    .is_some_and(|channel_id| source_channels.contains(&channel_id))
+            })
+            .map(|(id, _)| id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join("");
+
+        // 理由を設定 (募集メッセージの正規表現に影響しないよう、募集メッセージより前に置く)
+        let reason_message = reason
+            .as_ref()
+            .map(|reason| self.strings.reason_line.format(&[reason]))
+            .unwrap_or_default();
+        let limit_to_message = limit_to
+            .map(|limit_to| self.strings.limit_to_line.format(&[limit_to.mention()]))
+            .unwrap_or_default();
+        let follow_message = follow_duration
+            .map(|duration| {
+                self.strings
+                    .follow_line
+                    .format(&[duration_text(duration, self.strings)])
+            })
+            .unwrap_or_default();
+        // スレッドの作成や募集の投稿、リアクションの付与は応答期限の3秒を超えることがあるため応答を遅延
+        // (これ以降のエラーは遅延した応答を編集して返信する)
+        let ephemeral = self.app_config().discord.confirmation_ephemeral;
+        self.defer_response(ctx, interaction, ephemeral).await?;
+
+        // 設定によりスレッドを作成し、募集と結果をスレッドに投稿する (スレッドを作成できないチャンネルではそのまま投稿する)
+        let thread_id = if self.app_config().discord.use_thread {
+            let thread_name = self
+                .strings
+                .vote_thread_name
+                .format(&[&interaction.user.name])
+                .chars()
+                .take(THREAD_NAME_MAX_LENGTH)
+                .collect::<String>();
+            match create_public_thread_without_message(ctx, interaction.channel_id, thread_name)
+                .await
+            {
+                Ok(thread) => Some(thread.id),
+                Err(why) => {
+                    warn!(
+                        "スレッドを作成できないため、チャンネルに直接投稿します: {:?}",
+                        why
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 投票を構築
+        let mut vote = Vote {
+            guild_id,
+            channel_id: thread_id.unwrap_or(interaction.channel_id),
+            requester: interaction.user.id,
+            command_type: command_type.clone(),
+            header: format!(
+                "{}{message}{reason_message}{limit_to_message}{follow_message}",
+                self.strings.vote_header.format(&[
+                    source_channels
+                        .iter()
+                        .map(|channel_id| channel_id.mention().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    voice_member_mentions,
+                ]),
+            ),
+            source_channel_id: voice_channel_id,
+            additional_source_channel_ids,
+            reactors: vec![],
+            reason,
+            created_at: Instant::now(),
+            webhook: None,
+            return_after_minutes,
+            organizer_room: None,
+            timeout: settings.move_timeout,
+            closing_soon: false,
+            limit_to,
+            follow_duration,
+            triggered: false,
+        };
+
+        // メンション可能ロール (@everyone/@hereは禁止)
+        let mentionable_roles = guild
+            .roles
+            .values()
+            .filter(|role| role.mentionable)
+            .map(|role| role.id)
+            .collect::<Vec<RoleId>>();
+        // メッセージを送信 (設定によりWebhookで投稿する。Webhookはスレッドに投稿できないため、スレッドではBotとして投稿する)
+        if self.app_config().discord.use_webhook && thread_id.is_none() {
+            vote.webhook = Some(self.channel_webhook(ctx, interaction.channel_id).await?);
+        }
+        let message = match &vote.webhook {
+            Some(webhook) => webhook
+                .execute(&ctx, true, |w| {
+                    // メッセージを設定
+                    w.content(self.vote_content(&vote));
+                    // 参加ボタンを設定
+                    if !self.app_config().discord.vote_with_reaction {
+                        w.components(|c| self.vote_button(c, 0, false));
+                    }
+                    // 名前とアイコンを設定
+                    if let Some(name) = &self.app_config().discord.webhook_name {
+                        w.username(name);
+                    }
+                    if let Some(avatar_url) = &self.app_config().discord.webhook_avatar_url {
+                        w.avatar_url(avatar_url);
+                    }
+                    // メンション可能ロールのみに設定 (@everyone/@hereは禁止)
+                    w.allowed_mentions(|a| {
+                        a.parse(ParseValue::Users);
+                        a.roles(mentionable_roles);
+                        a
+                    });
+                    w
+                })
+                .await
+                .map_err(|_why| anyhow!(self.strings.post_failed))?
+                .context(self.strings.post_failed)?,
+            None => vote
+                .channel_id
+                .send_message(&ctx, |m| {
+                    // メッセージを設定
+                    m.content(self.vote_content(&vote));
+                    // 参加ボタンを設定
+                    if !self.app_config().discord.vote_with_reaction {
+                        m.components(|c| self.vote_button(c, 0, false));
+                    }
+                    // メンション可能ロールのみに設定 (@everyone/@hereは禁止)
+                    m.allowed_mentions(|a| {
+                        a.parse(ParseValue::Users);
+                        a.roles(mentionable_roles);
+                        a
+                    });
+                    m
+                })
+                .await
+                .map_err(|_why| anyhow!(self.strings.post_failed))?,
+        };
+        // リアクションで投票する場合は投票に使うすべての絵文字のリアクションを付与
+        // (レート制限などの一時的な失敗は再試行し、それでも付与できなければ投票できない募集を残さないよう削除する)
+        if self.app_config().discord.vote_with_reaction {
+            for emoji in &self.vote_emojis {
+                if let Err(why) =
+                    retry_with_backoff(VOTE_REACTION_RETRIES, || message.react(&ctx, emoji.clone()))
+                        .await
+                {
+                    warn!("募集メッセージへのリアクションの追加に失敗: {:?}", why);
+                    if let Err(why) =
+                        delete_vote_message(&*self.discord, ctx, vote.webhook.as_ref(), &message)
+                            .await
+                    {
+                        warn!(
+                            "リアクションを追加できなかった募集メッセージの削除に失敗: {:?}",
+                            why
+                        );
+                    }
+                    return Err(anyhow!(self.strings.vote_reaction_failed));
+                }
+            }
+        }
+
+        // 投票を記録
+        let message_id = message.id;
+        self.votes.lock().await.insert(message_id, vote);
+        self.save_votes().await;
+        Metrics::add(&self.metrics.votes_started, 1);
+        // クールダウンを開始
+        if !cooldown.is_zero() {
+            self.move_cooldowns
+                .lock()
+                .await
+                .insert((guild_id, interaction.user.id), Instant::now());
+        }
+
+        // 一定時間後にメッセージを削除
+        let timeout = settings.move_timeout;
+        self.arm_vote_timer(ctx, message, timeout).await;
+
+        // 遅延した応答を編集して返信をする
+        // 募集はすでに投稿されているため、返信に失敗してもエラーにはしない (エラーの返信で募集が失敗したように見せないようにする)
+        let how_to_join = if self.app_config().discord.vote_with_reaction {
+            let emojis = self
+                .vote_emojis
+                .iter()
+                .map(|emoji| emoji.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            self.strings.how_to_join_reaction.format(&[emojis])
+        } else {
+            self.strings.how_to_join_button.format(&[&self.vote_emoji])
+        };
+        let content = format!(
+            "{}{}",
+            self.app_config()
+                .messages
+                .vote_started
+                .as_deref()
+                .unwrap_or(self.strings.vote_started)
+                .format(&[how_to_join, command_type.to_text(self.strings)]),
+            thread_id
+                .map(|thread_id| self.strings.vote_thread_line.format(&[thread_id.mention()]))
+                .unwrap_or_default(),
+        );
+        match retry_with_backoff(VOTE_STARTED_REPLY_RETRIES, || {
+            interaction
+                .edit_original_interaction_response(&ctx, |response| response.content(&content))
+        })
+        .await
+        {
+            // 通常のメッセージで返信した場合は募集と一緒に削除
+            Ok(response) if !ephemeral => {
+                let ctx_clone = ctx.clone();
+                tokio::task::spawn(async move {
+                    // 制限時間が経ったら削除
+                    tokio::time::sleep(timeout).await;
+
+                    // メッセージを削除
+                    if let Err(why) = response.delete(ctx_clone).await {
+                        error!("返信メッセージの削除に失敗しました: {}", why);
+                    }
+                });
+            }
+            Ok(_) => {}
+            Err(why) => {
+                warn!("募集開始の返信に失敗: {:?}", why);
+            }
+        }
+
+        // 設定により主催者を先に移動してVCを作成しておく (失敗した場合は締め切り時に移動する)
+        let organizer_room_name = match &command_type {
+            CommandType::Move(channel_name) => Some(channel_name.clone()),
+            _ => None,
+        };
+        if let Some(channel_name) = organizer_room_name.filter(|_| {
+            self.app_config().discord.move_organizer_on_start && !self.app_config().discord.dry_run
+        }) {
+            match self
+                .move_organizer_on_start(ctx, settings, guild_id, interaction.user.id, channel_name)
+                .await
+            {
+                Ok(room_channel_id) => {
+                    if let Some(vote) = self.votes.lock().await.get_mut(&message_id) {
+                        vote.organizer_room = Some(room_channel_id);
+                    }
+                    self.save_votes().await;
+                }
+                Err(why) => {
+                    warn!("募集開始時の主催者の移動に失敗: {:?}", why);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 重複して募集していないか確認し、募集を開始し終えるまで同じチャンネルとVCを予約する
+    ///
+    /// 確認と予約を同じロックの中で行い、同時に実行したコマンドが両方とも確認を通らないようにする
+    async fn reserve_vote(
+        &self,
+        guild_id: GuildId,
+        interaction_id: InteractionId,
+        channel_id: ChannelId,
+        source_channels: &[ChannelId],
+    ) -> Result<VoteReservation> {
+        let votes = self.votes.lock().await;
+        let mut starting_votes = self
+            .starting_votes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // 同じVCで募集中の投票があれば重複して募集しない (一緒に集めるVCを含む)
+        let one_vote_per_source_channel = self.app_config().discord.one_vote_per_source_channel;
+        if one_vote_per_source_channel {
+            if let Some((message_id, vote)) = votes.iter().find(|(_, vote)| {
+                vote.source_channels()
+                    .iter()
+                    .any(|channel_id| source_channels.contains(channel_id))
+            }) {
+                return Err(anyhow!(self
+                    .strings
+                    .already_voting
+                    .format(&[message_id.link(vote.channel_id, Some(guild_id))])));
+            }
+        }
+
+        // 同じチャンネルに募集中の投票があれば重複して募集しない (どの募集へのリアクションか紛らわしくなるため)
+        if let Some((message_id, _)) = votes.iter().find(|(_, vote)| vote.channel_id == channel_id)
+        {
+            return Err(anyhow!(self
+                .strings
+                .already_voting_in_channel
+                .format(&[message_id.link(channel_id, Some(guild_id))])));
+        }
+
+        // 同じチャンネルやVCで募集を開始しているところであれば重複して募集しない
+        if starting_votes
+            .values()
+            .any(|(starting_channel_id, starting_sources)| {
+                *starting_channel_id == channel_id
+                    || (one_vote_per_source_channel
+                        && starting_sources
+                            .iter()
+                            .any(|channel_id| source_channels.contains(channel_id)))
+            })
+        {
+            return Err(anyhow!(self.strings.vote_starting));
+        }
+
+        starting_votes.insert(interaction_id, (channel_id, source_channels.to_vec()));
+        Ok(VoteReservation {
+            starting_votes: self.starting_votes.clone(),
+            interaction_id,
+        })
+    }
+
+    /// 主催者をVC作成チャンネルへ移動し、作成されたVCを返す
+    ///
+    /// create_channel_explicitly が有効な場合は、BotがVCを作成して主催者を移動する
+    async fn move_organizer_on_start(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_name: String,
+    ) -> Result<ChannelId> {
+        if !self.app_config().discord.create_channel_explicitly {
+            return self
+                .create_channel_for(ctx, settings, guild_id, user_id)
+                .await;
+        }
+
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?;
+        let channel_name = self.created_channel_name(settings, &guild, user_id, channel_name);
+        let channel_id = match self
+            .create_voice_channel(ctx, settings, guild_id, &channel_name)
+            .await
+        {
+            // カテゴリがいっぱいであれば、VC作成チャンネルで作成する (名前は締め切り時に変更する)
+            Err(why) if self.falls_back_to_create_channel(&why) => {
+                warn!("カテゴリがいっぱいのため、VC作成チャンネルでVCを作成します");
+                return self
+                    .create_channel_for(ctx, settings, guild_id, user_id)
+                    .await;
+            }
+            result => result?,
+        };
+        let retries = self.app_config().discord.move_retry_attempts;
+        if let Err(why) = retry_with_backoff(retries, || {
+            self.discord
+                .move_member(&ctx.http, guild_id, user_id, channel_id)
+        })
+        .await
+        {
+            // 主催者を移動できなければ作成したVCは使わないため削除 (締め切り時に作成し直す)
+            match self.discord.delete_channel(&ctx.http, channel_id).await {
+                Ok(_) => self.forget_created_channel(guild_id, channel_id).await,
+                Err(why) => warn!("使わなくなったVCの削除に失敗: {:?}", why),
+            }
+            return Err(anyhow!(why).context("移動に失敗"));
+        }
+        Ok(channel_id)
+    }
+
+    /// 設定したカテゴリにVCを作成する (VC作成チャンネルを使わない場合)
+    ///
+    /// 名前と人数制限は作成時に設定するため、作成されるのを待ったり名前を変更したりする必要はない
+    async fn create_voice_channel(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        channel_name: &str,
+    ) -> Result<ChannelId> {
+        // カテゴリのチャンネル数が上限に達していれば作成しない (キャッシュにあるチャンネルで確認する)
+        let category_channels = self.discord.guild(&ctx.cache, guild_id).map_or(0, |guild| {
+            guild
+                .channels
+                .values()
+                .filter(|channel| match channel {
+                    Channel::Guild(channel) => channel.parent_id == Some(settings.vc_category),
+                    _ => false,
+                })
+                .count()
+        });
+        if category_channels >= CATEGORY_MAX_CHANNELS {
+            return Err(anyhow::Error::new(CategoryFullError(
+                self.strings.category_full,
+            )));
+        }
+        self.check_created_channel_limit(guild_id).await?;
+
+        let user_limit = self
+            .app_config()
+            .discord
+            .vc_user_limit
+            .filter(|limit| *limit > 0);
+        let channel = self
+            .discord
+            .create_voice_channel(
+                &ctx.http,
+                guild_id,
+                channel_name,
+                settings.vc_category,
+                user_limit,
+            )
+            .await
+            .map_err(|why| {
+                if is_forbidden_error(&why) {
+                    anyhow!(self.strings.create_channel_no_permission)
+                } else if is_category_full_error(&why) {
+                    anyhow::Error::new(CategoryFullError(self.strings.category_full))
+                } else {
+                    anyhow!(why).context("VCの作成に失敗")
+                }
+            })?;
+        info!("VCを作成しました: {} ({})", channel.name, channel.id);
+        self.guild_created_channels
+            .lock()
+            .await
+            .entry(guild_id)
+            .or_default()
+            .insert(channel.id);
+        Ok(channel.id)
+    }
+
+    /// Botが作成して残っているVCの数が上限に達していればエラーを返す
+    async fn check_created_channel_limit(&self, guild_id: GuildId) -> Result<()> {
+        let max = match self.app_config().discord.max_created_channels_per_guild {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+        let count = self
+            .guild_created_channels
+            .lock()
+            .await
+            .get(&guild_id)
+            .map_or(0, HashSet::len);
+        if count >= max {
+            return Err(anyhow!(self.strings.created_channel_limit.format(&[max])));
+        }
+        Ok(())
+    }
+
+    /// 削除したVCをBotが作成して残っているVCから外す (作成できるVCの数を戻す)
+    async fn forget_created_channel(&self, guild_id: GuildId, channel_id: ChannelId) {
+        if let Some(channels) = self.guild_created_channels.lock().await.get_mut(&guild_id) {
+            channels.remove(&channel_id);
+        }
+    }
+
+    /// VCの作成に失敗したとき、VC作成チャンネルで作成し直すか (カテゴリがいっぱいで、設定されている場合)
+    fn falls_back_to_create_channel(&self, why: &anyhow::Error) -> bool {
+        self.app_config().discord.category_full_fallback && why.is::<CategoryFullError>()
+    }
+
+    /// /moveで作成するVCの名前を取得 (テンプレートが設定されていれば、入力した名前と主催者名から生成)
+    fn created_channel_name(
+        &self,
+        settings: &GuildSettings,
+        guild: &Guild,
+        user_id: UserId,
+        channel_name: String,
+    ) -> String {
+        let template = match &settings.created_channel_name_template {
+            Some(template) => template,
+            None => return channel_name,
+        };
+        let organizer = guild
+            .members
+            .get(&user_id)
+            .map(|member| member.display_name().to_string())
+            .unwrap_or_else(|| user_id.to_string());
+        let rendered = render_channel_name(template, &channel_name, &organizer);
+        if rendered.is_empty() {
+            channel_name
+        } else {
+            rendered
+        }
+    }
+
+    /// メンバーをVC作成チャンネルへ移動し、作成されたVCを返す
+    ///
+    /// VC作成チャンネルへの移動やVCの作成に失敗した場合は、予備のVC作成チャンネルで再試行する
+    async fn create_channel_for(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<ChannelId> {
+        let mut last_error = None;
+        for create_channel in settings.create_channels() {
+            // 移動前のチャンネルを取得 (VCから抜けている場合は移動しない)
+            let current_channel_id = cached_guild(&*self.discord, ctx, guild_id)
+                .await
+                .context("サーバーの取得に失敗")?
+                .voice_states
+                .get(&user_id)
+                .and_then(|voice_state| voice_state.channel_id)
+                .context(self.strings.not_in_voice)?;
+
+            // VC作成チャンネルへ移動し、VCが作成されるまで待つ
+            let result = match self
+                .discord
+                .move_member(&ctx.http, guild_id, user_id, create_channel)
+                .await
+            {
+                Ok(_) => {
+                    self.wait_for_created_channel(
+                        ctx,
+                        settings,
+                        guild_id,
+                        user_id,
+                        current_channel_id,
+                    )
+                    .await
+                }
+                Err(why) => Err(anyhow!(why).context("移動に失敗")),
+            };
+            match result {
+                Ok(channel_id) => return Ok(channel_id),
+                Err(why) => {
+                    warn!(
+                        "VC作成チャンネル {} でVCを作成できませんでした: {:?}",
+                        create_channel, why
+                    );
+                    last_error = Some(why);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!(self.strings.channel_not_created)))
+    }
+
+    /// VC作成チャンネルへ移動したメンバーが、作成されたVCへ移動されるまで待つ
+    ///
+    /// 最大 move_wait_seconds 秒まで通話状態を確認し、移動前のVCとVC作成チャンネル以外に入った時点で返す
+    async fn wait_for_created_channel(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        user_id: UserId,
+        previous_channel_id: ChannelId,
+    ) -> Result<ChannelId> {
+        let deadline = Instant::now() + Duration::from_secs(settings.move_wait_seconds);
+        loop {
+            // メンバーが移動した先のチャンネルを取得
+            let channel_id = self
+                .discord
+                .guild(&ctx.cache, guild_id)
+                .context("サーバーの取得に失敗")?
+                .voice_states
+                .get(&user_id)
+                .and_then(|voice_state| voice_state.channel_id)
+                .filter(|channel_id| {
+                    !settings.is_create_channel(*channel_id) && *channel_id != previous_channel_id
+                });
+            if let Some(channel_id) = channel_id {
+                return Ok(channel_id);
+            }
+
+            // 時間内にVCが作成されなければエラー
+            if Instant::now() >= deadline {
+                warn!(
+                    "{}秒以内にVC作成チャンネルからVCが作成されませんでした",
+                    settings.move_wait_seconds
+                );
+                return Err(anyhow!(self.strings.channel_not_created));
+            }
+            tokio::time::sleep(CHANNEL_CREATE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// 移動元のVCにいるメンバー (Botを除く) が全員参加したか
+    ///
+    /// 主催者は参加しているものとして扱う
+    fn everyone_joined(&self, ctx: &Context, vote: &Vote) -> bool {
+        // 参加者が最低人数に達していなければ締め切らない
+        if vote.participant_count() < self.app_config().discord.move_min_participants {
+            return false;
+        }
+
+        let guild = match self.discord.guild(&ctx.cache, vote.guild_id) {
+            Some(guild) => guild,
+            None => return false,
+        };
+        let source_channels = vote.source_channels();
+        let members = guild
+            .voice_states
+            .values()
+            .filter(|voice_state| {
+                voice_state
+                    .channel_id
+                    .is_some_and(|channel_id| source_channels.contains(&channel_id))
+            })
+            .map(|voice_state| voice_state.user_id)
+            .filter(|user_id| {
+                !guild
+                    .members
+                    .get(user_id)
+                    .is_some_and(|member| member.user.bot)
+            })
+            .collect::<Vec<UserId>>();
+
+        // 主催者しかいない場合は締め切らない
+        members.iter().any(|user_id| *user_id != vote.requester)
+            && members
+                .iter()
+                .all(|user_id| *user_id == vote.requester || vote.reactors.contains(user_id))
+    }
+
+    /// 主催者以外の参加者の操作で締め切れるか
+    ///
+    /// anyone_can_trigger が有効で、最低限の時間と人数を満たしている場合のみ締め切る
+    fn participant_can_trigger(&self, vote: &Vote) -> bool {
+        let app_config = self.app_config();
+        app_config.discord.anyone_can_trigger
+            && vote.created_at.elapsed() >= Duration::from_secs(app_config.discord.min_open_seconds)
+            && vote.participant_count() >= app_config.discord.move_min_participants
+    }
+
+    /// 参加者が最低人数に達したらすぐに締め切るか
+    ///
+    /// auto_trigger_on_threshold が有効な場合、誰の操作かに関わらず最低人数に達した時点で締め切る
+    fn threshold_reached(&self, vote: &Vote) -> bool {
+        let app_config = self.app_config();
+        app_config.discord.auto_trigger_on_threshold
+            && vote.participant_count() >= app_config.discord.move_min_participants
+    }
+
+    /// 投票の締め切りを開始したことを記録する
+    ///
+    /// すでに締め切りを開始していればfalseを返し、同時に締め切ろうとしても移動は1回だけ行う
+    /// (投票の記録がない場合は確認できないため、そのまま締め切る)
+    async fn claim_trigger(&self, message_id: MessageId) -> bool {
+        match self.votes.lock().await.get_mut(&message_id) {
+            Some(vote) => !std::mem::replace(&mut vote.triggered, true),
+            None => true,
+        }
+    }
+
+    /// 移動に失敗した投票を、もう一度締め切れるように戻す
+    async fn release_trigger(&self, message_id: MessageId) {
+        if let Some(vote) = self.votes.lock().await.get_mut(&message_id) {
+            vote.triggered = false;
+        }
+    }
+
+    /// 締め切りを開始していなければ、参加したメンバーを移動する
+    async fn trigger_move(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        message: &Message,
+        mention_user: UserId,
+        mention_channel_id: CommandType,
+        participants: Vec<UserId>,
+    ) -> Result<()> {
+        // 同時に届いた操作ですでに締め切りを開始していれば何もしない
+        if !self.claim_trigger(message.id).await {
+            info!(
+                "すでに締め切りを開始している募集のため移動しません: {}",
+                message.id
+            );
+            return Ok(());
+        }
+        let result = self
+            .execute_move(
+                ctx,
+                guild_id,
+                message,
+                mention_user,
+                mention_channel_id,
+                participants,
+            )
+            .await;
+        if result.is_err() {
+            self.release_trigger(message.id).await;
+        }
+        result
+    }
+
+    /// 募集メッセージのボタンが押されたときの処理
+    async fn on_move_button(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+    ) -> Result<()> {
+        // 参加ボタンでなければ無視
+        if interaction.data.custom_id != VOTE_BUTTON_ID {
+            return Ok(());
+        }
+
+        let user_id = interaction.user.id;
+        let message_id = interaction.message.id;
+
+        // 移動後もあとから参加した人を移動している募集であれば、押した人を移動先へ移動
+        if self.follows.lock().await.contains_key(&message_id) {
+            let to_channel_id = self.follow_reactor(ctx, message_id, user_id).await?;
+            interaction
+                .create_interaction_response(&ctx, |response| {
+                    response
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|message| {
+                            message.ephemeral(true).content(
+                                self.strings
+                                    .follow_moved
+                                    .format(&[to_channel_id.mention().to_string()]),
+                            )
+                        })
+                })
+                .await
+                .context("移動の通知に失敗")?;
+            return Ok(());
+        }
+
+        // 進行中の投票を取得
+        let vote = self
+            .votes
+            .lock()
+            .await
+            .get(&message_id)
+            .cloned()
+            .context(self.strings.vote_closed)?;
+
+        // 主催者以外は参加 (参加済みであれば参加を取り消す)
+        if user_id != vote.requester {
+            let (joined, count, close) = {
+                let mut votes = self.votes.lock().await;
+                let vote = votes
+                    .get_mut(&message_id)
+                    .context(self.strings.vote_closed)?;
+                let joined = match vote.reactors.iter().position(|id| *id == user_id) {
+                    Some(index) => {
+                        vote.reactors.remove(index);
+                        false
+                    }
+                    None => {
+                        vote.reactors.push(user_id);
+                        true
+                    }
+                };
+                // 移動元のVCの全員が参加した場合や、設定により参加者の操作や最低人数に達したことで締め切れる場合は締め切る
+                let close = joined
+                    && (self.everyone_joined(ctx, vote)
+                        || self.participant_can_trigger(vote)
+                        || self.threshold_reached(vote));
+                (joined, vote.reactors.len(), close)
+            };
+            self.save_votes().await;
+
+            // 締め切る場合はすぐにメンバーを移動
+            if close {
+                return self.close_by_button(ctx, interaction).await;
+            }
+
+            // ボタンの人数を更新
+            interaction
+                .create_interaction_response(&ctx, |response| {
+                    response
+                        .kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|message| {
+                            message.components(|c| self.vote_button(c, count, false))
+                        })
+                })
+                .await
+                .context("ボタンの更新に失敗")?;
+
+            // 押した人にだけ結果を通知
+            let content = if joined {
+                self.strings.joined.format(&[vote.requester.mention()])
+            } else {
+                self.strings.left.to_string()
+            };
+            if let Err(why) = interaction
+                .create_followup_message(&ctx, |message| message.ephemeral(true).content(content))
+                .await
+            {
+                warn!("参加の通知に失敗: {:?}", why);
+            }
+            return Ok(());
+        }
+
+        // 募集開始から最低限の時間が経っていなければ移動しない
+        let min_open = Duration::from_secs(self.app_config().discord.min_open_seconds);
+        if let Some(remaining) = min_open.checked_sub(vote.created_at.elapsed()) {
+            return Err(anyhow!(self
+                .strings
+                .wait_more
+                .format(&[remaining.as_secs() + 1])));
+        }
+
+        // 参加者が最低人数に達していなければ移動しない
+        let min_participants = self.app_config().discord.move_min_participants;
+        if vote.participant_count() < min_participants {
+            return Err(anyhow!(self
+                .strings
+                .not_enough_participants
+                .format(&[min_participants - vote.participant_count()])));
+        }
+
+        self.close_by_button(ctx, interaction).await
+    }
+
+    /// 参加ボタンを無効にして募集を締め切り、メンバーを移動する
+    async fn close_by_button(
+        &self,
+        ctx: &Context,
+        interaction: &MessageComponentInteraction,
+    ) -> Result<()> {
+        let vote = self
+            .votes
+            .lock()
+            .await
+            .get(&interaction.message.id)
+            .cloned()
+            .context(self.strings.vote_closed)?;
+
+        // ボタンを無効にして締め切る
+        let count = vote.reactors.len();
+        interaction
+            .create_interaction_response(&ctx, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| {
+                        message.components(|c| self.vote_button(c, count, true))
+                    })
+            })
+            .await
+            .context("ボタンの更新に失敗")?;
+
+        // 主催者とボタンを押した人を押した順に移動
+        let guild_id = interaction.guild_id.context("サーバーの取得に失敗")?;
+        let participants = std::iter::once(vote.requester)
+            .chain(vote.reactors.iter().copied())
+            .collect::<Vec<UserId>>();
+        if let Err(why) = self
+            .trigger_move(
+                ctx,
+                guild_id,
+                &interaction.message,
+                vote.requester,
+                vote.command_type.clone(),
+                participants,
+            )
+            .await
+        {
+            // もう一度押せるようにボタンを戻す
+            if let Err(why) = interaction
+                .edit_original_interaction_response(&ctx, |response| {
+                    response.components(|c| self.vote_button(c, count, false))
+                })
+                .await
+            {
+                warn!("ボタンの更新に失敗: {:?}", why);
+            }
+            // すでに応答しているため、エラーは追加のメッセージで押した人にだけ通知
+            interaction
+                .create_followup_message(&ctx, |message| {
+                    message.ephemeral(true).content(why.to_string())
+                })
+                .await
+                .context("エラーメッセージの送信に失敗")?;
+        }
+
+        Ok(())
+    }
+
+    /// 一定時間後に募集メッセージを削除する処理を予約
+    ///
+    /// 締め切ったときに中止できるように記録する
+    async fn arm_vote_timer(&self, ctx: &Context, message: Message, delay: Duration) {
+        let ctx_clone = ctx.clone();
+        let discord = self.discord.clone();
+        let votes = self.votes.clone();
+        let vote_timers = self.vote_timers.clone();
+        let vote_store = self.vote_store.clone();
+        let metrics = self.metrics.clone();
+        let app_config = self.app_config.clone();
+        let min_participants = self.app_config().discord.move_min_participants;
+        let warn_before = self
+            .app_config()
+            .discord
+            .warn_before_timeout_seconds
+            .map(Duration::from_secs);
+        let vote_message = self.vote_message.clone();
+        let strings = self.strings;
+        let message_id = message.id;
+        let timer = tokio::task::spawn(async move {
+            // 締め切りの少し前に、まもなく締め切ることを募集メッセージに表示
+            // (同じ処理の中で待つため、締め切ったときに削除の予約と一緒に中止される)
+            let mut delay = delay;
+            if let Some(warn_before) = warn_before {
+                let warn_delay = delay.saturating_sub(warn_before);
+                tokio::time::sleep(warn_delay).await;
+                delay -= warn_delay;
+
+                let vote = {
+                    let mut votes = votes.lock().await;
+                    let vote = votes
+                        .get_mut(&message.id)
+                        .filter(|vote| !vote.closing_soon)
+                        .map(|vote| {
+                            vote.closing_soon = true;
+                            vote.clone()
+                        });
+                    if vote.is_some() {
+                        save_votes(&vote_store, &votes, &metrics);
+                    }
+                    vote
+                };
+                if let Some(vote) = vote {
+                    let app_config = app_config
+                        .read()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone();
+                    let content = vote_content(&vote, &vote_message, strings, &app_config);
+                    if let Err(why) =
+                        edit_vote_message(&*discord, &ctx_clone, message.id, &vote, content).await
+                    {
+                        warn!("締め切り前の通知の表示に失敗: {:?}", why);
+                    }
+                }
+            }
+
+            // 指定した時間が経ったら削除
+            tokio::time::sleep(delay).await;
+
+            // 投票の記録を削除
+            vote_timers.lock().await.remove(&message.id);
+            let vote = {
+                let mut votes = votes.lock().await;
+                let vote = votes.remove(&message.id);
+                save_votes(&vote_store, &votes, &metrics);
+                vote
+            };
+            if vote.is_some() {
+                Metrics::add(&metrics.votes_timed_out, 1);
+            }
+
+            // 時間切れの時点の参加者の人数に応じて、移動しなかったことを通知
+            // (主催者以外に誰も参加しなかった場合と、参加者が最低人数に達しなかった場合)
+            let notice = match vote.as_ref().map(Vote::participant_count) {
+                Some(count) if count <= 1 => Some(
+                    app_config
+                        .read()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .messages
+                        .vote_expired
+                        .clone()
+                        .unwrap_or_else(|| strings.vote_expired.to_string()),
+                ),
+                Some(count) if count < min_participants => {
+                    Some(strings.vote_not_established.format(&[min_participants]))
+                }
+                _ => None,
+            };
+            if let Some(notice) = notice {
+                if let Err(why) = message
+                    .channel_id
+                    .send_message(&ctx_clone, |m| m.content(notice))
+                    .await
+                {
+                    warn!("募集が成立しなかったことの通知に失敗: {:?}", why);
+                }
+            }
+
+            // メッセージを削除 (すでに締め切られた投票のメッセージは、締め切った処理に任せる)
+            let vote = match vote {
+                Some(vote) => vote,
+                None => return,
+            };
+            match delete_vote_message(&*discord, &ctx_clone, vote.webhook.as_ref(), &message).await
+            {
+                Ok(_) => {}
+                Err(why) => {
+                    error!("メッセージの削除に失敗しました: {}", why);
+                }
+            }
+        });
+        self.vote_timers.lock().await.insert(message_id, timer);
+    }
+
+    /// 元のVCへ戻す処理を開始し、再起動後も戻せるように予定をファイルに保存する
+    async fn arm_return(&self, ctx: &Context, pending: PendingReturn) {
+        let ctx = ctx.clone();
+        let discord = self.discord.clone();
+        let returns = self.returns.clone();
+        let vote_store = self.vote_store.clone();
+        let strings = self.strings;
+        let message_id = pending.message_id;
+        let task_pending = pending.clone();
+        // 戻し終えた処理が予定を消すのは、ここで予定を記録した後になる
+        let mut scheduled = self.returns.lock().await;
+        let task = tokio::task::spawn(async move {
+            return_members(&ctx, &*discord, &task_pending, strings).await;
+
+            // 戻し終えた予定を保存ファイルから除く
+            let mut returns = returns.lock().await;
+            returns.remove(&task_pending.message_id);
+            save_returns(&vote_store, &returns);
+        });
+        if let Some((_, old_task)) = scheduled.insert(message_id, (pending, task)) {
+            old_task.abort();
+        }
+        save_returns(&self.vote_store, &scheduled);
+    }
+
+    /// 進行中の投票をファイルに保存
+    async fn save_votes(&self) {
+        save_votes(&self.vote_store, &*self.votes.lock().await, &self.metrics);
+    }
+
+    /// 募集メッセージが削除されたときに投票を取り消す
+    ///
+    /// Botが締め切って削除した場合は投票がすでにないため何もしない
+    async fn on_vote_message_delete(&self, message_ids: &[MessageId]) {
+        let removed = {
+            let mut votes = self.votes.lock().await;
+            let mut follows = self.follows.lock().await;
+            message_ids
+                .iter()
+                .filter(|message_id| {
+                    // 移動後もあとから参加した人を移動している募集も終える
+                    let follow = follows.remove(message_id).is_some();
+                    votes.remove(message_id).is_some() || follow
+                })
+                .copied()
+                .collect::<Vec<MessageId>>()
+        };
+        if removed.is_empty() {
+            return;
+        }
+        self.save_votes().await;
+
+        // 時間切れで削除する処理 (締め切り前の通知を含む) を中止
+        let mut vote_timers = self.vote_timers.lock().await;
+        for message_id in &removed {
+            if let Some(timer) = vote_timers.remove(message_id) {
+                timer.abort();
+            }
+            info!(
+                "募集メッセージが削除されたため募集を取り消しました: {}",
+                message_id
+            );
+        }
+    }
+
+    /// 保存した投票を読み込み、再起動前の募集を再開する
+    ///
+    /// 締め切りを過ぎた募集はすぐに削除する
+    async fn resume_votes(&self, ctx: &Context) -> Result<()> {
+        let stored_votes = self.vote_store.load::<StoredVote>()?;
+        let now = Local::now().timestamp();
+        for stored in stored_votes {
+            // 再開済みの投票は無視 (再接続したとき)
+            if self.votes.lock().await.contains_key(&stored.message_id) {
+                continue;
+            }
+
+            // 募集メッセージを取得 (削除されていれば再開しない)
+            let message = match stored.channel_id.message(&ctx, stored.message_id).await {
+                Ok(message) => message,
+                Err(why) => {
+                    warn!("募集メッセージが取得できないため再開しません: {:?}", why);
+                    continue;
+                }
+            };
+
+            // Webhookで投稿した募集はWebhookを取得し直す
+            let webhook = if stored.use_webhook {
+                match self.channel_webhook(ctx, stored.channel_id).await {
+                    Ok(webhook) => Some(webhook),
+                    Err(why) => {
+                        warn!("募集のWebhookの取得に失敗: {:?}", why);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let elapsed = Duration::from_secs((now - stored.created_at).max(0) as u64);
+            let remaining = Duration::from_secs((stored.deadline - now).max(0) as u64);
+            let vote = Vote {
+                guild_id: stored.guild_id,
+                channel_id: stored.channel_id,
+                requester: stored.requester,
+                command_type: stored.command_type,
+                header: stored.header,
+                source_channel_id: stored.source_channel_id,
+                additional_source_channel_ids: stored.additional_source_channel_ids,
+                reactors: stored.reactors,
+                reason: stored.reason,
+                created_at: Instant::now()
+                    .checked_sub(elapsed)
+                    .unwrap_or_else(Instant::now),
+                webhook,
+                return_after_minutes: stored.return_after_minutes,
+                organizer_room: stored.organizer_room,
+                timeout: Duration::from_secs((stored.deadline - stored.created_at).max(0) as u64),
+                closing_soon: stored.closing_soon,
+                limit_to: stored.limit_to,
+                follow_duration: stored.follow_duration_seconds.map(Duration::from_secs),
+                triggered: false,
+            };
+            self.votes.lock().await.insert(message.id, vote);
+
+            // 残りの時間で削除を予約 (締め切りを過ぎていればすぐに削除)
+            self.arm_vote_timer(ctx, message, remaining).await;
+        }
+
+        // 再開できなかった投票を保存ファイルから除く
+        self.save_votes().await;
+        self.votes_resumed.store(true, Ordering::Relaxed);
+
+        // 再起動前の元のVCへ戻す予定を再開 (戻す時刻を大きく過ぎた予定は取り消す)
+        let stored_returns = self.vote_store.load_returns::<PendingReturn>()?;
+        for pending in stored_returns {
+            // 再開済みの予定は無視 (再接続したとき)
+            if self.returns.lock().await.contains_key(&pending.message_id) {
+                continue;
+            }
+            if now - pending.deadline > RETURN_RESUME_GRACE_SECONDS {
+                info!(
+                    "戻す時刻を過ぎているため元のVCへ戻す予定を取り消します: {}",
+                    pending.message_id
+                );
+                continue;
+            }
+            self.arm_return(ctx, pending).await;
+        }
+        // 取り消した予定を保存ファイルから除く
+        save_returns(&self.vote_store, &*self.returns.lock().await);
+        Ok(())
+    }
+
+    /// 募集メッセージの参加ボタンを作成
+    fn vote_button<'a>(
+        &self,
+        components: &'a mut CreateComponents,
+        count: usize,
+        disabled: bool,
+    ) -> &'a mut CreateComponents {
+        components.create_action_row(|row| {
+            row.create_button(|button| {
+                button
+                    .custom_id(VOTE_BUTTON_ID)
+                    .style(ButtonStyle::Primary)
+                    .emoji(self.vote_emoji.clone())
+                    .label(self.strings.vote_button.format(&[count]))
+                    .disabled(disabled)
+            })
+        })
+    }
+
+    /// 短時間に同じリアクションのイベントが届いた場合は重複として扱う
+    async fn is_duplicate_reaction(&self, reaction: &Reaction) -> bool {
+        let user_id = match reaction.user_id {
+            Some(user_id) => user_id,
+            None => return false,
+        };
+        let now = Instant::now();
+        let mut recent_reactions = self.recent_reactions.lock().await;
+
+        // 期限切れの記録を削除
+        recent_reactions
+            .retain(|_, processed_at| now.duration_since(*processed_at) < REACTION_DEDUP_WINDOW);
+
+        // 記録済みであれば重複
+        let key = (reaction.message_id, user_id, reaction.emoji.to_string());
+        if recent_reactions.contains_key(&key) {
+            return true;
+        }
+        recent_reactions.insert(key, now);
+        false
+    }
+
+    /// 主催者のリアクションを外し、しばらくしたら消えるメッセージで案内する
+    ///
+    /// 押し直しを重複とみなさないよう記録も消し、もう一度押せるようにする
+    async fn retry_reaction_later(
+        &self,
+        ctx: &Context,
+        reaction: &Reaction,
+        notice: String,
+    ) -> Result<()> {
+        // リアクションを外す
+        if let Err(why) = reaction.delete(&ctx).await {
+            warn!("リアクションの削除に失敗: {:?}", why);
+        }
+        if let Some(user_id) = reaction.user_id {
+            self.recent_reactions.lock().await.remove(&(
+                reaction.message_id,
+                user_id,
+                reaction.emoji.to_string(),
+            ));
+        }
+
+        // しばらくしたら消えるメッセージで案内
+        let notice = reaction
+            .channel_id
+            .send_message(&ctx, |m| m.content(notice))
+            .await
+            .context("メッセージの送信に失敗")?;
+        let ctx_clone = ctx.clone();
+        tokio::task::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            if let Err(why) = notice.delete(ctx_clone).await {
+                error!("メッセージの削除に失敗しました: {}", why);
+            }
+        });
+        Ok(())
+    }
+
+    /// リアクションの絵文字に対応する投票の動作 (投票の絵文字でなければNone)
+    fn vote_action(&self, emoji: &ReactionType) -> Option<VoteAction> {
+        self.vote_emojis.contains(emoji).then_some(VoteAction::Join)
+    }
+
+    /// リアクションが押されたときの処理
+    async fn on_move_reaction(&self, ctx: &Context, reaction: &Reaction) -> Result<()> {
+        // ボタンで投票する場合や投票の絵文字でない場合はリアクションを無視
+        if !self.app_config().discord.vote_with_reaction {
+            return Ok(());
+        }
+        match self.vote_action(&reaction.emoji) {
+            Some(VoteAction::Join) => {}
+            None => return Ok(()),
+        }
+
+        // 重複して届いたイベントであれば無視
+        if self.is_duplicate_reaction(reaction).await {
+            return Ok(());
+        }
+
+        // Bot自身のリアクション (リアクションで投票する場合に募集メッセージへ付与したもの) は無視
+        let bot_id = self.discord.current_user_id(&ctx.cache);
+        if reaction.user_id == Some(bot_id) {
+            return Ok(());
+        }
+
+        // 移動後もあとから参加した人を移動している募集であれば、リアクションした人を移動先へ移動
+        if self.follows.lock().await.contains_key(&reaction.message_id) {
+            let user_id = reaction.user_id.context("ユーザーIDの取得に失敗")?;
+            self.follow_reactor(ctx, reaction.message_id, user_id)
+                .await?;
+            return Ok(());
+        }
+
+        // 進行中の投票がすべて分かっている場合は、投票でないメッセージへのリアクションをAPIを呼ばずに無視
+        // (保存した投票を再開できなかった場合は、メッセージを取得して募集メッセージの形式かで判断する)
+        if self.votes_resumed.load(Ordering::Relaxed)
+            && !self.votes.lock().await.contains_key(&reaction.message_id)
+        {
+            return Ok(());
+        }
+
+        // リアクションを追加したメッセージを取得
+        let message = self
+            .discord
+            .message(&ctx.http, reaction.channel_id, reaction.message_id)
+            .await
+            .context("メッセージの取得に失敗")?;
+
+        // リアクションのメッセージが募集メッセージでなければ無視
+        if !self.is_vote_message(&message, bot_id).await {
+            return Ok(());
+        }
+
+        // 進行中の投票から主催者と移動先を取得 (MESSAGE_CONTENTがなくても本文を読まずに済む)
+        let tracked = self
+            .votes
+            .lock()
+            .await
+            .get(&message.id)
+            .map(|vote| (vote.requester, vote.command_type.clone()));
+
+        // 進行中の投票でなく、メッセージが募集メッセージの形式でなければ無視
+        if tracked.is_none()
+            && !self
+                .vote_message_regexes
+                .iter()
+                .any(|regex| regex.is_match(&message.content))
+        {
+            return Ok(());
+        }
+
+        // リアクションをしたユーザーを取得
+        let user_id = reaction.user_id.context("ユーザーIDの取得に失敗")?;
+
+        // リアクションを追加した順番を記録
+        let mut everyone_joined = false;
+        let mut participant_trigger = false;
+        let mut threshold_trigger = false;
+        if let Some(vote) = self.votes.lock().await.get_mut(&message.id) {
+            if !vote.reactors.contains(&user_id) {
+                vote.reactors.push(user_id);
+            }
+            // 移動元のVCの全員がリアクションした場合はすぐに締め切る
+            everyone_joined = self.everyone_joined(ctx, vote);
+            // 設定により参加者のリアクションでも締め切る
+            participant_trigger = self.participant_can_trigger(vote);
+            // 設定により最低人数に達した時点で締め切る
+            threshold_trigger = self.threshold_reached(vote);
+        }
+        self.save_votes().await;
+
+        // メッセージのメンションユーザーと移動先を取得
+        let (mention_user, mention_channel_id) = match tracked {
+            Some(tracked) => tracked,
+            None => self.parse_vote_message(&message.content)?,
+        };
+
+        // リアクションを追加した人がメンションされた人でなければ参加予定の人数のみ更新 (全員がリアクションした場合や参加者が締め切れる場合、最低人数に達した場合を除く)
+        if mention_user != user_id && !everyone_joined && !participant_trigger && !threshold_trigger
+        {
+            return self.update_vote_count(ctx, message.id).await;
+        }
+
+        // 募集開始から最低限の時間が経っていなければ移動しない (全員がリアクションした場合や最低人数に達して締め切る場合を除く)
+        let created_at = self
+            .votes
+            .lock()
+            .await
+            .get(&message.id)
+            .map(|vote| vote.created_at)
+            .filter(|_| !everyone_joined && !threshold_trigger);
+        let min_open = Duration::from_secs(self.app_config().discord.min_open_seconds);
+        if let Some(remaining) =
+            created_at.and_then(|created_at| min_open.checked_sub(created_at.elapsed()))
+        {
+            self.retry_reaction_later(
+                ctx,
+                reaction,
+                format!(
+                    "{}{}",
+                    user_id.mention(),
+                    self.strings.wait_more.format(&[remaining.as_secs() + 1]),
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        // リアクションを追加した人リストを取得 (投票の絵文字ごとに取得し、複数の絵文字でリアクションした人は1人とする)
+        let guild_id = reaction.guild_id.context("サーバーの取得に失敗")?;
+        let mut participants = Vec::new();
+        let mut reaction_requests = 0;
+        for emoji in &self.vote_emojis {
+            let (reaction_users, requests) = fetch_reaction_users(
+                &*self.discord,
+                &ctx.http,
+                reaction,
+                emoji,
+                self.app_config().discord.max_reactors,
+            )
+            .await?;
+            reaction_requests += requests;
+            for user in reaction_users {
+                if !user.bot && !participants.contains(&user.id) {
+                    participants.push(user.id);
+                }
+            }
+        }
+        info!(
+            "リアクションを追加したユーザーの取得に使ったAPI呼び出し: {}回",
+            reaction_requests,
+        );
+
+        // 参加者が最低人数に達していなければ移動しない
+        let min_participants = self.app_config().discord.move_min_participants;
+        if participants.len() < min_participants {
+            self.retry_reaction_later(
+                ctx,
+                reaction,
+                format!(
+                    "{}{}",
+                    user_id.mention(),
+                    self.strings
+                        .not_enough_participants
+                        .format(&[min_participants - participants.len()]),
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        // リアクションを追加した順に並べ替え (Discordからはユーザー順で返ってくるため、記録した順番を使う)
+        // 順番が記録されていないユーザーは最後に移動する
+        if let Some(vote) = self.votes.lock().await.get(&message.id) {
+            participants.sort_by_key(|participant| {
+                vote.reactors
+                    .iter()
+                    .position(|id| id == participant)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        // メンバーを移動
+        self.trigger_move(
+            ctx,
+            guild_id,
+            &message,
+            mention_user,
+            mention_channel_id,
+            participants,
+        )
+        .await
+    }
+
+    /// 募集メッセージか
+    ///
+    /// 進行中の投票として記録したメッセージIDで判断し (Webhookで投稿した募集も含む)、
+    /// 記録にない場合 (保存した投票を再開できなかった場合など) のみBotが投稿したメッセージかで判断する
+    async fn is_vote_message(&self, message: &Message, bot_id: UserId) -> bool {
+        self.votes.lock().await.contains_key(&message.id) || message.author.id == bot_id
+    }
+
+    /// リアクションが外されたときの処理
+    async fn on_move_reaction_remove(&self, ctx: &Context, reaction: &Reaction) -> Result<()> {
+        // ボタンで投票する場合や投票の絵文字でない場合は無視
+        if !self.app_config().discord.vote_with_reaction
+            || self.vote_action(&reaction.emoji).is_none()
+        {
+            return Ok(());
+        }
+        let user_id = reaction.user_id.context("ユーザーIDの取得に失敗")?;
+
+        // すぐに付け直したリアクションを重複とみなさないよう記録を消す
+        self.recent_reactions.lock().await.remove(&(
+            reaction.message_id,
+            user_id,
+            reaction.emoji.to_string(),
+        ));
+
+        // 進行中の投票でなければ何もしない
+        if !self.votes.lock().await.contains_key(&reaction.message_id) {
+            return Ok(());
+        }
+
+        // ほかの投票の絵文字でもリアクションしていれば、参加をやめていないものとする
+        for emoji in self
+            .vote_emojis
+            .iter()
+            .filter(|emoji| **emoji != reaction.emoji)
+        {
+            let (users, _) = fetch_reaction_users(
+                &*self.discord,
+                &ctx.http,
+                reaction,
+                emoji,
+                self.app_config().discord.max_reactors,
+            )
+            .await?;
+            if users.iter().any(|user| user.id == user_id) {
+                return Ok(());
+            }
+        }
+
+        // 進行中の投票の参加者から外す
+        let removed = match self.votes.lock().await.get_mut(&reaction.message_id) {
+            Some(vote) => {
+                let count = vote.reactors.len();
+                vote.reactors.retain(|id| *id != user_id);
+                vote.reactors.len() != count
+            }
+            None => false,
+        };
+        if removed {
+            self.save_votes().await;
+            self.update_vote_count(ctx, reaction.message_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 募集を締め切り、参加したメンバーを移動する
+    ///
+    /// participants は移動するユーザー (移動する順)
+    async fn execute_move(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        message: &Message,
+        mention_user: UserId,
+        mention_channel_id: CommandType,
+        participants: Vec<UserId>,
+    ) -> Result<()> {
+        // サーバーの設定を取得
+        let settings = self.guild_config(guild_id)?;
+
+        // 主催者がボイスチャンネルにいるか確認
+        let user_id = mention_user;
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?;
+        let voice_state = guild
+            .voice_states
+            .get(&user_id)
+            .context(self.strings.not_in_voice)?;
+        let current_channel_id = voice_state
+            .channel_id
+            .context("ボイスチャンネルのIDの取得に失敗")?;
+
+        // 移動の理由と元のVCへ戻るまでの時間、投稿したWebhook、募集開始時に主催者を移動したVC、募集を開始した時刻を取得
+        let (
+            reason,
+            return_after_minutes,
+            vote_webhook,
+            vote_source_channel_id,
+            organizer_room,
+            vote_created_at,
+            limit_to,
+            follow_duration,
+            additional_source_channel_ids,
+        ) = self
+            .votes
+            .lock()
+            .await
+            .get(&message.id)
+            .map(|vote| {
+                (
+                    vote.reason.clone(),
+                    vote.return_after_minutes,
+                    vote.webhook.clone(),
+                    Some(vote.source_channel_id),
+                    vote.organizer_room,
+                    Some(vote.created_at),
+                    vote.limit_to,
+                    vote.follow_duration,
+                    vote.additional_source_channel_ids.clone(),
+                )
+            })
+            .unwrap_or_default();
+
+        // 主催者が募集開始時に移動したVCにまだいれば、そのVCを移動先にし、募集を開始したVCを移動元とする
+        let organizer_room = organizer_room.filter(|room| *room == current_channel_id);
+        let source_channel_id = match organizer_room {
+            Some(_) => vote_source_channel_id.unwrap_or(current_channel_id),
+            None => current_channel_id,
+        };
+
+        // 新しいVCを作成して移動するか
+        let new_channel = matches!(mention_channel_id, CommandType::Move(_));
+        // 主催者も移動するか (move_toでは設定により主催者を移動しないことができる)
+        let move_requester = self.app_config().discord.move_requester || new_channel;
+        // 試行モードでは確認のみ行い、メンバーの移動やVCの変更はしない
+        let dry_run = self.app_config().discord.dry_run;
+
+        // 移動先チャンネルを取得/作成
+        let to_channel_id = match mention_channel_id {
+            CommandType::MoveTo(channel_id) => {
+                // 権限を確認
+                check_connect_permission(
+                    &*self.discord,
+                    ctx,
+                    channel_id,
+                    user_id,
+                    self.destination_channel_types(),
+                    self.strings,
+                )
+                .await?;
+                self.check_move_to_category(ctx, channel_id).await?;
+
+                channel_id
+            }
+            // 主催者が募集を開始したときにいたVCへそのまま移動する
+            CommandType::MoveHere(channel_id) => channel_id,
+            // 作成した複数のVCへ参加者をランダムに分けて移動する
+            CommandType::MoveRandom(count) => {
+                return self
+                    .execute_random_move(ctx, guild_id, message, mention_user, count, participants)
+                    .await;
+            }
+            CommandType::Move(_) if dry_run => {
+                // VCを作成しないため、VC作成チャンネルに入れるかのみ確認
+                check_connect_permission(
+                    &*self.discord,
+                    ctx,
+                    settings.vc_create_channel,
+                    user_id,
+                    &[ChannelType::Voice],
+                    self.strings,
+                )
+                .await?;
+
+                settings.vc_create_channel
+            }
+            CommandType::Move(channel_name) => {
+                // テンプレートが設定されていれば、入力した名前と主催者名からVCの名前を生成
+                let channel_name =
+                    self.created_channel_name(&settings, &guild, user_id, channel_name);
+                // 設定によりVC作成チャンネルを使わず、BotがVCを作成する (作成時に名前と人数制限を設定する)
+                let create_explicitly = self.app_config().discord.create_channel_explicitly;
+                let voice_channel_id = match organizer_room {
+                    // 募集開始時に作成したVCを使う
+                    Some(room) => room,
+                    // 設定したカテゴリにVCを作成する
+                    None if create_explicitly => match self
+                        .create_voice_channel(ctx, &settings, guild_id, &channel_name)
+                        .await
+                    {
+                        // カテゴリがいっぱいであれば、VC作成チャンネルで作成して名前を変更する
+                        Err(why) if self.falls_back_to_create_channel(&why) => {
+                            warn!("カテゴリがいっぱいのため、VC作成チャンネルでVCを作成します");
+                            self.create_channel_for(ctx, &settings, guild_id, user_id)
+                                .await?
+                        }
+                        result => result?,
+                    },
+                    // まず一人VC作成チャンネルへ移動し、VCが作成されて移動されるまで待つ
+                    None => {
+                        self.create_channel_for(ctx, &settings, guild_id, user_id)
+                            .await?
+                    }
+                };
+
+                // 除外対象か確認
+                if settings.vc_ignored_channels.contains(&voice_channel_id) {
+                    return Err(anyhow!(self.strings.ignored_channel));
+                }
+
+                // チャンネルを取得
+                let channel = self
+                    .discord
+                    .channel(&ctx.cache, &ctx.http, voice_channel_id)
+                    .await
+                    .context("チャンネルの取得に失敗")?
+                    .guild()
+                    .context("チャンネルがサーバーのチャンネルではありません")?;
+
+                // 設定したカテゴリの中か確認
+                if channel.parent_id != Some(settings.vc_category) {
+                    return Err(anyhow!(self.strings.wrong_category));
+                }
+
+                // 既に名前が付けられたVCは名前を変更しない (VC作成チャンネルの名前を含むVCのみ変更する)
+                let rename = if create_explicitly && channel.name == channel_name {
+                    // 作成時に名前を設定している (カテゴリがいっぱいでVC作成チャンネルで作成した場合は変更する)
+                    false
+                } else if self.app_config().discord.rename_only_default_named {
+                    let mut create_channel_names = Vec::new();
+                    for create_channel in settings.create_channels() {
+                        create_channel_names.extend(create_channel.name(&ctx).await);
+                    }
+                    let default_named = create_channel_names.is_empty()
+                        || create_channel_names
+                            .iter()
+                            .any(|name| channel.name.contains(name));
+                    if !default_named {
+                        warn!(
+                            "VCの名前が初期の名前ではないため変更しません: {} (VC作成チャンネル: {})",
+                            channel.name,
+                            create_channel_names.join(", ")
+                        );
+                    }
+                    default_named
+                } else {
+                    true
+                };
+
+                // VCの名前を変更 (人数制限が設定されていれば合わせて設定)
+                let user_limit = self
+                    .app_config()
+                    .discord
+                    .vc_user_limit
+                    .filter(|limit| *limit > 0 && !create_explicitly);
+                if rename || user_limit.is_some() {
+                    self.discord
+                        .edit_channel(
+                            &ctx.http,
+                            voice_channel_id,
+                            rename.then_some(channel_name),
+                            user_limit.map(u64::from),
+                        )
+                        .await
+                        .context("チャンネルの名前の変更に失敗")?;
+                }
+
+                // 空になったら削除するVCとして記録
+                if self
+                    .app_config()
+                    .discord
+                    .empty_channel_grace_seconds
+                    .is_some()
+                {
+                    self.created_channels.lock().await.insert(voice_channel_id);
+                }
+
+                voice_channel_id
+            }
+        };
+
+        // VCの状態が変わっている可能性があるため、ギルドを再取得
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?;
+
+        // 移動するメンバーを取得 (キャッシュの通話状態で先に絞り込み、キャッシュにないメンバーのみAPIで取得する)
+        let targets = move_targets(
+            &participants,
+            &guild.voice_states,
+            to_channel_id,
+            (!move_requester).then_some(mention_user),
+        )
+        .into_iter()
+        .map(|user_id| (user_id, guild.members.get(&user_id).cloned()))
+        .collect::<Vec<(UserId, Option<Member>)>>();
+        let member_requests = AtomicUsize::new(0);
+        let member_requests_ref = &member_requests;
+        let members = stream::iter(targets)
+            // メンバーを取得
+            .map(|(user_id, cached)| async move {
+                match cached {
+                    Some(member) => Ok(member),
+                    None => {
+                        member_requests_ref.fetch_add(1, Ordering::Relaxed);
+                        self.discord.member(&ctx.http, guild_id, user_id).await
+                    }
+                }
+            })
+            // 同時に実行するリクエスト数を制限し、リアクションを追加した順を保つ
+            .buffered(self.app_config().discord.max_concurrent_fetches.max(1))
+            .try_collect::<Vec<Member>>()
+            .await
+            .context("メンバーの取得に失敗")?;
+        info!(
+            "移動するメンバーの取得に使ったAPI呼び出し: {}回 (移動対象 {}人)",
+            member_requests.load(Ordering::Relaxed),
+            members.len(),
+        );
+
+        // 主催者が移動できるメンバーを制限していれば、それ以外のメンバーは移動しない (主催者は常に移動できる)
+        let (members, not_allowed_members): (Vec<Member>, Vec<Member>) =
+            members.into_iter().partition(|member| {
+                member.user.id == mention_user
+                    || limit_to.is_none_or(|limit_to| limit_to.allows(member))
+            });
+        if !not_allowed_members.is_empty() {
+            info!(
+                "移動できるメンバーに含まれないため移動しないメンバー: {}人",
+                not_allowed_members.len()
+            );
+        }
+
+        // 設定で移動対象外にしたメンバーは移動しない (主催者も対象外にできる)
+        let app_config = self.app_config();
+        let (exempt_members, members): (Vec<Member>, Vec<Member>) = members
+            .into_iter()
+            .partition(|member| is_move_exempt(&app_config.discord, member));
+        if !exempt_members.is_empty() {
+            info!(
+                "移動対象外のため移動しないメンバー: {}人",
+                exempt_members.len()
+            );
+        }
+
+        // 移動先のVCに入る権限がないメンバーは移動しない (主催者だけでなくメンバーごとに確認する)
+        let to_channel = self
+            .discord
+            .channel(&ctx.cache, &ctx.http, to_channel_id)
+            .await
+            .context("チャンネルの取得に失敗")?
+            .guild()
+            .context("チャンネルがサーバーのチャンネルではありません")?;
+        let (members, no_permission_members): (Vec<Member>, Vec<Member>) =
+            members.into_iter().partition(|member| {
+                match guild.user_permissions_in(&to_channel, member) {
+                    Ok(permissions) => permissions.connect(),
+                    Err(why) => {
+                        warn!("メンバーの権限の取得に失敗: {} {:?}", member.user.id, why);
+                        false
+                    }
+                }
+            });
+        if !no_permission_members.is_empty() {
+            info!(
+                "移動先のVCに入る権限がないため移動しないメンバー: {}人",
+                no_permission_members.len()
+            );
+        }
+
+        // 移動先のVCに人数制限があれば、空いている人数までに絞る (主催者を先に数え、残りはリアクションを追加した順)
+        // 同時に移動するため、移動の順ではなくここで先着順にする
+        let user_limit = new_channel
+            .then_some(self.app_config().discord.vc_user_limit)
+            .flatten()
+            .map(u64::from)
+            .or(to_channel.user_limit)
+            .filter(|limit| *limit > 0);
+        let capacity = user_limit.map(|limit| {
+            let occupants = guild
+                .voice_states
+                .values()
+                .filter(|voice_state| voice_state.channel_id == Some(to_channel_id))
+                .count();
+            (limit as usize).saturating_sub(occupants)
+        });
+        let (members, full_members) = fit_to_capacity(members, capacity, mention_user);
+        if !full_members.is_empty() {
+            info!(
+                "移動先のVCが満員のため移動しないメンバー: {}人",
+                full_members.len()
+            );
+        }
+
+        // メンバーをリアクションを追加した順に移動 (人数制限がある場合は先着順になる)
+        // 同時に実行する数を制限し、一時的な失敗は待ち時間を倍にしながら再試行し、再試行しても移動できなかったメンバーのみ失敗とする
+        let retries = self.app_config().discord.move_retry_attempts;
+        let move_results = stream::iter(members)
+            .map(|member| async move {
+                // 試行モードでは移動したものとして扱う
+                if dry_run {
+                    return (member, Ok(()));
+                }
+                let result = retry_with_backoff(retries, || {
+                    self.discord
+                        .move_member(&ctx.http, guild_id, member.user.id, to_channel_id)
+                })
+                .await;
+                (member, result)
+            })
+            .buffered(self.app_config().discord.max_concurrent_moves.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        let mut moved_members = Vec::new();
+        let mut failed_members = Vec::new();
+        for (member, result) in move_results {
+            match result {
+                Ok(_) => moved_members.push(member),
+                Err(why) => {
+                    warn!("メンバーの移動に失敗: {} {:?}", member.user.id, why);
+                    failed_members.push(member);
+                }
+            }
+        }
+
+        // ステージチャンネルに移動した場合は主催者をスピーカーにする
+        if self.app_config().discord.promote_requester_on_stage && move_requester && !dry_run {
+            if let Err(why) =
+                promote_on_stage(&*self.discord, ctx, to_channel_id, mention_user).await
+            {
+                warn!("主催者のスピーカーへの変更に失敗: {:?}", why);
+            }
+        }
+
+        // 移動したメンバーに移動先をDMで通知 (DMが無効なメンバーがいても残りのメンバーには送信する)
+        let mut dm_failed_count = 0;
+        if self.app_config().discord.dm_on_move && !dry_run {
+            let content = self
+                .strings
+                .moved_dm
+                .format(&[guild.name.clone(), to_channel_id.mention().to_string()]);
+            for member in &moved_members {
+                if let Err(why) = self
+                    .discord
+                    .send_dm(&ctx.http, member.user.id, &content)
+                    .await
+                {
+                    warn!(
+                        "移動したメンバーへのDMの送信に失敗 (DMが無効になっている可能性があります): {} {:?}",
+                        member.user.id, why
+                    );
+                    dm_failed_count += 1;
+                }
+            }
+        }
+
+        // 主催者以外の移動した人数
+        let moved_count = moved_participant_count(&moved_members, mention_user);
+        // 移動したメンバーの説明
+        let moved_summary = if move_requester {
+            self.strings.moved_with
+        } else {
+            self.strings.moved_by
+        }
+        .format(&[mention_user.mention().to_string(), moved_count.to_string()]);
+
+        if !dry_run {
+            Metrics::add(&self.metrics.members_moved, moved_members.len());
+            Metrics::add(&self.metrics.move_failures, failed_members.len());
+        }
+
+        // 集計用に移動の記録を1行のJSONで出力 (設定によりユーザーIDと理由を隠す)
+        let salt = self.app_config().discord.log_user_id_salt.clone();
+        info!(
+            target: "move_event",
+            "{}",
+            serde_json::json!({
+                "guild_id": guild_id.0.to_string(),
+                "organizer_id": log_user_id(salt.as_deref(), mention_user),
+                "destination_channel_id": to_channel_id.0.to_string(),
+                "moved": moved_members.len(),
+                "failed": failed_members.len(),
+                "vote_duration_seconds": vote_created_at.map(|created_at| created_at.elapsed().as_secs()),
+                "new_channel": new_channel,
+                "dry_run": dry_run,
+                "reason": reason.as_ref().filter(|_| salt.is_none()),
+            }),
+        );
+
+        // 統計のために移動を記録
+        if !dry_run {
+            if let Err(why) = self
+                .move_history
+                .record(guild_id, mention_user, moved_members.len())
+            {
+                warn!("移動の記録に失敗: {:?}", why);
+            }
+        }
+
+        // 移動先VCのチャットへ通知
+        if self.app_config().discord.announce_in_destination && !dry_run {
+            if let Err(why) = to_channel_id
+                .send_message(&ctx, |message| {
+                    message.content(self.strings.arrived.format(&[&moved_summary]))
+                })
+                .await
+            {
+                warn!("移動先VCへの通知に失敗: {:?}", why);
+            }
+        }
+
+        // 募集の記録を消し、募集メッセージを片付ける (時間切れで削除する処理は中止)
+        let vote = self.votes.lock().await.remove(&message.id);
+        self.save_votes().await;
+        if let Some(timer) = self.vote_timers.lock().await.remove(&message.id) {
+            timer.abort();
+        }
+        let header = vote.map(|vote| vote.header).unwrap_or_default();
+        match follow_duration.filter(|_| !dry_run) {
+            // 指定した時間が経つまで、あとから参加した人も移動先へ移動する
+            Some(duration) => {
+                let mut moved = moved_members
+                    .iter()
+                    .map(|member| member.user.id)
+                    .collect::<HashSet<UserId>>();
+                moved.insert(mention_user);
+                let follow = Follow {
+                    guild_id,
+                    to_channel_id,
+                    limit_to,
+                    moved,
+                    channel_id: message.channel_id,
+                    webhook: vote_webhook.clone(),
+                    header,
+                };
+                self.start_follow(ctx, message, follow, duration).await;
+            }
+            None => {
+                complete_vote_message(
+                    &*self.discord,
+                    ctx,
+                    vote_webhook.as_ref(),
+                    message,
+                    &header,
+                    &to_channel_id.mention().to_string(),
+                    self.strings,
+                    self.app_config().discord.delete_message_on_complete,
+                )
+                .await?;
+            }
+        }
+        // 結果を送信
+        let mut create_message = CreateMessage::default();
+        create_message.content(format!(
+            "{}{}",
+            if dry_run { "[DRY RUN] " } else { "" },
+            self.app_config()
+                .messages
+                .moved_result
+                .as_deref()
+                .unwrap_or(self.strings.moved_result)
+                .format(&[moved_summary.clone(), to_channel_id.mention().to_string()]),
+        ));
+        create_message.embed(|embed| {
+            embed.title(self.strings.moved_members_title);
+            embed.description(truncate_with_ellipsis(
+                moved_members
+                    .iter()
+                    .map(|member| member.mention().to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                EMBED_DESCRIPTION_MAX_LENGTH,
+            ));
+            if !failed_members.is_empty() {
+                embed.field(
+                    self.strings.move_failed_field,
+                    mention_lines(&failed_members, self.strings.and_more),
+                    false,
+                );
+            }
+            if !no_permission_members.is_empty() {
+                embed.field(
+                    self.strings.no_connect_permission_field,
+                    mention_lines(&no_permission_members, self.strings.and_more),
+                    false,
+                );
+            }
+            if !not_allowed_members.is_empty() {
+                embed.field(
+                    self.strings.limit_to_ignored_field,
+                    mention_lines(&not_allowed_members, self.strings.and_more),
+                    false,
+                );
+            }
+            if !exempt_members.is_empty() {
+                embed.field(
+                    self.strings.move_exempt_field,
+                    mention_lines(&exempt_members, self.strings.and_more),
+                    false,
+                );
+            }
+            if !full_members.is_empty() {
+                embed.field(
+                    self.strings.destination_full_field,
+                    mention_lines(&full_members, self.strings.and_more),
+                    false,
+                );
+            }
+            if let Some(reason) = &reason {
+                embed.field(self.strings.reason_field, reason, false);
+            }
+            // 設定した色とフッターを付ける (DMの送信に失敗した人数はフッターの先頭に表示する)
+            let app_config = self.app_config();
+            if let Some(color) = app_config.discord.embed_color {
+                embed.colour(color);
+            }
+            let footer_text = (dm_failed_count > 0)
+                .then(|| self.strings.moved_dm_failed.format(&[dm_failed_count]))
+                .into_iter()
+                .chain(app_config.discord.embed_footer.clone())
+                .collect::<Vec<String>>();
+            if !footer_text.is_empty() {
+                embed.footer(|footer| footer.text(footer_text.join("\n")));
+            }
+            embed
+        });
+        let result_message = self
+            .discord
+            .send_message(&ctx.http, message.channel_id, create_message)
+            .await
+            .context("メッセージの送信に失敗")?;
+
+        // 主催者に結果をDMで送信
+        if self.app_config().discord.dm_summary_to_requester && !dry_run {
+            let failed_mentions = failed_members
+                .iter()
+                .map(|member| member.mention().to_string())
+                .collect::<Vec<String>>();
+            let summary = self.strings.dm_summary.format(&[
+                guild.name.clone(),
+                to_channel_id.mention().to_string(),
+                moved_count.to_string(),
+                if failed_mentions.is_empty() {
+                    self.strings.none.to_string()
+                } else {
+                    failed_mentions.join(" ")
+                },
+            ]);
+            if let Err(why) = self
+                .discord
+                .send_dm(&ctx.http, mention_user, &summary)
+                .await
+            {
+                warn!(
+                    "主催者へのDMの送信に失敗 (DMが無効になっている可能性があります): {:?}",
+                    why
+                );
+            }
+        }
+
+        // 結果のメッセージに完了のリアクションを付与
+        if let Some(emoji) = result_ack_emoji(&self.app_config()).ok().flatten() {
+            if let Err(why) = self
+                .discord
+                .react(
+                    &ctx.http,
+                    result_message.channel_id,
+                    result_message.id,
+                    emoji,
+                )
+                .await
+            {
+                warn!("完了のリアクションの追加に失敗: {:?}", why);
+            }
+        }
+
+        // 一定時間後に元のVCへ戻す
+        if let Some(minutes) = return_after_minutes.filter(|_| !dry_run) {
+            let mut user_ids = moved_members
+                .iter()
+                .map(|member| member.user.id)
+                .collect::<Vec<UserId>>();
+            if move_requester && !user_ids.contains(&mention_user) {
+                user_ids.push(mention_user);
+            }
+            let pending = PendingReturn {
+                message_id: message.id,
+                guild_id,
+                text_channel_id: message.channel_id,
+                to_channel_id,
+                source_channel_id,
+                user_ids,
+                deadline: Local::now().timestamp() + 60 * minutes as i64,
+            };
+            self.arm_return(ctx, pending).await;
+        }
+
+        // 移動元のVC (一緒に集めたVCを含む) が空になっていれば削除 (元のVCへ戻る予定がある場合は残す)
+        if self.app_config().discord.cleanup_empty_source
+            && !dry_run
+            && return_after_minutes.is_none()
+        {
+            let mut cleanup_channels = vec![source_channel_id];
+            for channel_id in additional_source_channel_ids {
+                if !cleanup_channels.contains(&channel_id) {
+                    cleanup_channels.push(channel_id);
+                }
+            }
+            cleanup_channels.retain(|channel_id| *channel_id != to_channel_id);
+            self.cleanup_empty_sources(ctx, &settings, guild_id, &cleanup_channels)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// 指定した数の新しいVCを作成し、参加者をシャッフルして順番に割り振って移動する
+    async fn execute_random_move(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        message: &Message,
+        mention_user: UserId,
+        room_count: usize,
+        participants: Vec<UserId>,
+    ) -> Result<()> {
+        // サーバーの設定を取得
+        let settings = self.guild_config(guild_id)?;
+
+        // 主催者がボイスチャンネルにいるか確認
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?;
+        let source_channel_id = guild
+            .voice_states
+            .get(&mention_user)
+            .and_then(|voice_state| voice_state.channel_id)
+            .context(self.strings.not_in_voice)?;
+
+        // 移動の理由と投稿したWebhook、移動できるメンバーの制限、募集を開始した時刻、一緒に集めたVCを取得
+        let (reason, vote_webhook, limit_to, vote_created_at, additional_source_channel_ids) = self
+            .votes
+            .lock()
+            .await
+            .get(&message.id)
+            .map(|vote| {
+                (
+                    vote.reason.clone(),
+                    vote.webhook.clone(),
+                    vote.limit_to,
+                    Some(vote.created_at),
+                    vote.additional_source_channel_ids.clone(),
+                )
+            })
+            .unwrap_or_default();
+        // 試行モードではVCを作成せず、分け方の確認のみ行う
+        let dry_run = self.app_config().discord.dry_run;
+
+        // VCにいる参加者を取得 (キャッシュにないメンバーのみAPIで取得する)
+        let targets = participants
+            .iter()
+            .filter_map(|participant| guild.voice_states.get(participant))
+            .filter(|voice_state| voice_state.channel_id.is_some())
+            .map(|voice_state| {
+                (
+                    voice_state.user_id,
+                    guild.members.get(&voice_state.user_id).cloned(),
+                )
+            })
+            .collect::<Vec<(UserId, Option<Member>)>>();
+        let members = stream::iter(targets)
+            .map(|(user_id, cached)| async move {
+                match cached {
+                    Some(member) => Ok(member),
+                    None => self.discord.member(&ctx.http, guild_id, user_id).await,
+                }
+            })
+            .buffered(self.app_config().discord.max_concurrent_fetches.max(1))
+            .try_collect::<Vec<Member>>()
+            .await
+            .context("メンバーの取得に失敗")?;
+
+        // 主催者が移動できるメンバーを制限していれば、それ以外のメンバーは移動しない (主催者は常に移動できる)
+        let (members, not_allowed_members): (Vec<Member>, Vec<Member>) =
+            members.into_iter().partition(|member| {
+                member.user.id == mention_user
+                    || limit_to.is_none_or(|limit_to| limit_to.allows(member))
+            });
+
+        // 設定で移動対象外にしたメンバーは移動しない (主催者も対象外にできる)
+        let app_config = self.app_config();
+        let (exempt_members, members): (Vec<Member>, Vec<Member>) = members
+            .into_iter()
+            .partition(|member| is_move_exempt(&app_config.discord, member));
+
+        // 作成するVCのカテゴリに入る権限がないメンバーは移動しない (作成したVCはカテゴリの権限を引き継ぐ)
+        let category = guild
+            .channels
+            .get(&settings.vc_category)
+            .and_then(|channel| channel.clone().guild());
+        let (mut members, no_permission_members): (Vec<Member>, Vec<Member>) =
+            members.into_iter().partition(|member| {
+                let Some(category) = &category else {
+                    return true;
+                };
+                match guild.user_permissions_in(category, member) {
+                    Ok(permissions) => permissions.connect(),
+                    Err(why) => {
+                        warn!("メンバーの権限の取得に失敗: {} {:?}", member.user.id, why);
+                        false
+                    }
+                }
+            });
+
+        // 参加者をシャッフル (参加者より多くのVCは作成しない)
+        members.shuffle(&mut rand::thread_rng());
+        let room_count = room_count.min(members.len()).max(1);
+
+        // VCを作成 (途中で失敗した場合は作成したVCを削除して中止する)
+        let mut rooms: Vec<(String, ChannelId)> = Vec::new();
+        for number in 1..=room_count {
+            let channel_name = self.strings.random_room_name.format(&[number]);
+            let channel_id = if dry_run {
+                settings.vc_create_channel
+            } else {
+                match self
+                    .create_voice_channel(ctx, &settings, guild_id, &channel_name)
+                    .await
+                {
+                    Ok(channel_id) => channel_id,
+                    Err(why) => {
+                        for (_, channel_id) in &rooms {
+                            match self.discord.delete_channel(&ctx.http, *channel_id).await {
+                                Ok(_) => self.forget_created_channel(guild_id, *channel_id).await,
+                                Err(why) => {
+                                    warn!("作成したVCの削除に失敗: {} {:?}", channel_id, why)
+                                }
+                            }
+                        }
+                        return Err(why);
+                    }
+                }
+            };
+            rooms.push((channel_name, channel_id));
+        }
+
+        // 空になったら削除するVCとして記録
+        if self
+            .app_config()
+            .discord
+            .empty_channel_grace_seconds
+            .is_some()
+            && !dry_run
+        {
+            self.created_channels
+                .lock()
+                .await
+                .extend(rooms.iter().map(|(_, channel_id)| *channel_id));
+        }
+
+        // シャッフルした順にVCへ割り振って移動 (一時的な失敗は待ち時間を倍にしながら再試行する)
+        let retries = self.app_config().discord.move_retry_attempts;
+        let rooms_ref = &rooms;
+        let move_results = stream::iter(members.into_iter().enumerate())
+            .map(|(index, member)| async move {
+                let room = index % rooms_ref.len();
+                // 試行モードでは移動したものとして扱う
+                if dry_run {
+                    return (room, member, Ok(()));
+                }
+                let (_, to_channel_id) = rooms_ref[room];
+                let result = retry_with_backoff(retries, || {
+                    self.discord
+                        .move_member(&ctx.http, guild_id, member.user.id, to_channel_id)
+                })
+                .await;
+                (room, member, result)
+            })
+            .buffered(self.app_config().discord.max_concurrent_moves.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        let mut groups = vec![Vec::new(); rooms.len()];
+        let mut failed_members = Vec::new();
+        for (room, member, result) in move_results {
+            match result {
+                Ok(_) => groups[room].push(member),
+                Err(why) => {
+                    warn!("メンバーの移動に失敗: {} {:?}", member.user.id, why);
+                    failed_members.push(member);
+                }
+            }
+        }
+
+        // 移動したメンバーの説明と移動先
+        let moved_total = groups.iter().map(Vec::len).sum::<usize>();
+        let moved_count = groups
+            .iter()
+            .flatten()
+            .filter(|member| member.user.id != mention_user)
+            .count();
+        let moved_summary = self
+            .strings
+            .moved_with
+            .format(&[mention_user.mention().to_string(), moved_count.to_string()]);
+        let destinations = rooms
+            .iter()
+            .map(|(_, channel_id)| channel_id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        if !dry_run {
+            Metrics::add(&self.metrics.members_moved, moved_total);
+            Metrics::add(&self.metrics.move_failures, failed_members.len());
+        }
+
+        // 集計用に移動の記録を1行のJSONで出力 (設定によりユーザーIDと理由を隠す)
+        let salt = self.app_config().discord.log_user_id_salt.clone();
+        info!(
+            target: "move_event",
+            "{}",
+            serde_json::json!({
+                "guild_id": guild_id.0.to_string(),
+                "organizer_id": log_user_id(salt.as_deref(), mention_user),
+                "destination_channel_ids": rooms
+                    .iter()
+                    .map(|(_, channel_id)| channel_id.0.to_string())
+                    .collect::<Vec<String>>(),
+                "moved": moved_total,
+                "failed": failed_members.len(),
+                "vote_duration_seconds": vote_created_at.map(|created_at| created_at.elapsed().as_secs()),
+                "new_channel": true,
+                "dry_run": dry_run,
+                "reason": reason.as_ref().filter(|_| salt.is_none()),
+            }),
+        );
+
+        // 統計のために移動を記録
+        if !dry_run {
+            if let Err(why) = self
+                .move_history
+                .record(guild_id, mention_user, moved_total)
+            {
+                warn!("移動の記録に失敗: {:?}", why);
+            }
+        }
+
+        // 募集の記録を消し、募集メッセージを片付ける (時間切れで削除する処理は中止)
+        let vote = self.votes.lock().await.remove(&message.id);
+        self.save_votes().await;
+        if let Some(timer) = self.vote_timers.lock().await.remove(&message.id) {
+            timer.abort();
+        }
+        let header = vote.map(|vote| vote.header).unwrap_or_default();
+        complete_vote_message(
+            &*self.discord,
+            ctx,
+            vote_webhook.as_ref(),
+            message,
+            &header,
+            &destinations,
+            self.strings,
+            self.app_config().discord.delete_message_on_complete,
+        )
+        .await?;
+
+        // 結果を送信 (作成したVCごとに分けたメンバーを表示する)
+        let mentions = |members: &[Member]| mention_lines(members, self.strings.and_more);
+        let mut create_message = CreateMessage::default();
+        create_message.content(format!(
+            "{}{}",
+            if dry_run { "[DRY RUN] " } else { "" },
+            self.app_config()
+                .messages
+                .moved_result
+                .as_deref()
+                .unwrap_or(self.strings.moved_result)
+                .format(&[moved_summary, destinations]),
+        ));
+        create_message.embed(|embed| {
+            embed.title(self.strings.random_groups_title);
+            for ((channel_name, channel_id), members) in rooms.iter().zip(&groups) {
+                let value = if members.is_empty() {
+                    self.strings.none.to_string()
+                } else {
+                    mentions(members)
+                };
+                let name = if dry_run {
+                    channel_name.clone()
+                } else {
+                    format!("{} ({})", channel_name, channel_id.mention())
+                };
+                embed.field(name, value, true);
+            }
+            if !failed_members.is_empty() {
+                embed.field(
+                    self.strings.move_failed_field,
+                    mentions(&failed_members),
+                    false,
+                );
+            }
+            if !no_permission_members.is_empty() {
+                embed.field(
+                    self.strings.no_connect_permission_field,
+                    mentions(&no_permission_members),
+                    false,
+                );
+            }
+            if !not_allowed_members.is_empty() {
+                embed.field(
+                    self.strings.limit_to_ignored_field,
+                    mentions(&not_allowed_members),
+                    false,
+                );
+            }
+            if !exempt_members.is_empty() {
+                embed.field(
+                    self.strings.move_exempt_field,
+                    mentions(&exempt_members),
+                    false,
+                );
+            }
+            if let Some(reason) = &reason {
+                embed.field(self.strings.reason_field, reason, false);
+            }
+            // 設定した色とフッターを付ける
+            let app_config = self.app_config();
+            if let Some(color) = app_config.discord.embed_color {
+                embed.colour(color);
+            }
+            if let Some(footer) = &app_config.discord.embed_footer {
+                embed.footer(|f| f.text(footer));
+            }
+            embed
+        });
+        let result_message = self
+            .discord
+            .send_message(&ctx.http, message.channel_id, create_message)
+            .await
+            .context("メッセージの送信に失敗")?;
+
+        // 結果のメッセージに完了のリアクションを付与
+        if let Some(emoji) = result_ack_emoji(&self.app_config()).ok().flatten() {
+            if let Err(why) = self
+                .discord
+                .react(
+                    &ctx.http,
+                    result_message.channel_id,
+                    result_message.id,
+                    emoji,
+                )
+                .await
+            {
+                warn!("完了のリアクションの追加に失敗: {:?}", why);
+            }
+        }
+
+        // 移動元のVC (一緒に集めたVCを含む) が空になっていれば削除
+        if self.app_config().discord.cleanup_empty_source && !dry_run {
+            let mut cleanup_channels = vec![source_channel_id];
+            for channel_id in additional_source_channel_ids {
+                if !cleanup_channels.contains(&channel_id) {
+                    cleanup_channels.push(channel_id);
+                }
+            }
+            cleanup_channels.retain(|channel_id| !rooms.iter().any(|(_, room)| room == channel_id));
+            self.cleanup_empty_sources(ctx, &settings, guild_id, &cleanup_channels)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// 移動後も指定した時間が経つまで、募集メッセージに参加した人を移動先へ移動する
+    ///
+    /// 時間が経ったら、締め切った募集として募集メッセージを片付ける
+    async fn start_follow(
+        &self,
+        ctx: &Context,
+        message: &Message,
+        follow: Follow,
+        duration: Duration,
+    ) {
+        let to_channel_id = follow.to_channel_id;
+        let webhook = follow.webhook.clone();
+        let header = follow.header.clone();
+        let count = follow.moved.len();
+        self.follows.lock().await.insert(message.id, follow);
+
+        // 移動先と残りの時間を募集メッセージに表示 (ボタンで投票する場合は締め切り時に無効にしたボタンを戻す)
+        let content = format!(
+            "{header}\n\n{}",
+            self.strings.vote_following.format(&[
+                to_channel_id.mention().to_string(),
+                duration_text(duration, self.strings),
+            ])
+        );
+        let with_button = !self.app_config().discord.vote_with_reaction;
+        let result = match &webhook {
+            Some(webhook) => webhook
+                .edit_message(&ctx, message.id, |m| {
+                    if with_button {
+                        m.components(|c| self.vote_button(c, count, false));
+                    }
+                    m.content(content)
+                })
+                .await
+                .map(|_| ()),
+            None => message
+                .channel_id
+                .edit_message(&ctx, message.id, |m| {
+                    if with_button {
+                        m.components(|c| self.vote_button(c, count, false));
+                    }
+                    m.content(content)
+                })
+                .await
+                .map(|_| ()),
+        };
+        if let Err(why) = result {
+            warn!("移動後の募集メッセージの編集に失敗: {:?}", why);
+        }
+
+        // 指定した時間が経ったら移動をやめ、募集メッセージを片付ける
+        let ctx_clone = ctx.clone();
+        let discord = self.discord.clone();
+        let follows = self.follows.clone();
+        let vote_timers = self.vote_timers.clone();
+        let strings = self.strings;
+        let delete = self.app_config().discord.delete_message_on_complete;
+        let message = message.clone();
+        let message_id = message.id;
+        let timer = tokio::task::spawn(async move {
+            tokio::time::sleep(duration).await;
+
+            vote_timers.lock().await.remove(&message.id);
+            if follows.lock().await.remove(&message.id).is_none() {
+                return;
+            }
+            if let Err(why) = complete_vote_message(
+                &*discord,
+                &ctx_clone,
+                webhook.as_ref(),
+                &message,
+                &header,
+                &to_channel_id.mention().to_string(),
+                strings,
+                delete,
+            )
+            .await
+            {
+                error!("メッセージの削除に失敗しました: {}", why);
+            }
+        });
+        self.vote_timers.lock().await.insert(message_id, timer);
+    }
+
+    /// 移動後に参加した人を移動先へ移動する
+    ///
+    /// すでに移動した人は移動しないため、同じ参加が何度届いても移動は1回だけ行う
+    async fn follow_reactor(
+        &self,
+        ctx: &Context,
+        message_id: MessageId,
+        user_id: UserId,
+    ) -> Result<ChannelId> {
+        let (guild_id, to_channel_id, limit_to) = {
+            let mut follows = self.follows.lock().await;
+            let follow = follows
+                .get_mut(&message_id)
+                .context(self.strings.vote_closed)?;
+            if !follow.moved.insert(user_id) {
+                return Ok(follow.to_channel_id);
+            }
+            (follow.guild_id, follow.to_channel_id, follow.limit_to)
+        };
+
+        let result = self
+            .move_follower(ctx, guild_id, user_id, to_channel_id, limit_to)
+            .await;
+        if result.is_err() {
+            // 移動できなかった場合は、もう一度参加したときに移動できるよう記録を消す
+            if let Some(follow) = self.follows.lock().await.get_mut(&message_id) {
+                follow.moved.remove(&user_id);
+            }
+        }
+        result.map(|_| to_channel_id)
+    }
+
+    /// 移動後に参加した人が、VCにいて移動できるメンバーであれば移動先へ移動する
+    async fn move_follower(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        user_id: UserId,
+        to_channel_id: ChannelId,
+        limit_to: Option<LimitTo>,
+    ) -> Result<()> {
+        // VCにいない人は移動できない
+        let in_voice = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?
+            .voice_states
+            .get(&user_id)
+            .is_some_and(|voice_state| voice_state.channel_id.is_some());
+        if !in_voice {
+            return Err(anyhow!(self.strings.not_in_voice));
+        }
+
+        // 移動対象外のメンバーと、主催者が移動できるメンバーを制限していれば確認
+        let has_exemptions = {
+            let discord = &self.app_config().discord;
+            !discord.move_exempt_user_ids.is_empty() || !discord.move_exempt_role_ids.is_empty()
+        };
+        if limit_to.is_some() || has_exemptions {
+            let member = fetch_member(&*self.discord, ctx, guild_id, user_id)
+                .await
+                .context("メンバーの取得に失敗")?;
+            if is_move_exempt(&self.app_config().discord, &member) {
+                return Err(anyhow!(self.strings.follow_exempt));
+            }
+            if limit_to.is_some_and(|limit_to| !limit_to.allows(&member)) {
+                return Err(anyhow!(self.strings.follow_not_allowed));
+            }
+        }
+
+        // 移動先のVCに入る権限を確認
+        check_connect_permission(
+            &*self.discord,
+            ctx,
+            to_channel_id,
+            user_id,
+            self.destination_channel_types(),
+            self.strings,
+        )
+        .await?;
+
+        let retries = self.app_config().discord.move_retry_attempts;
+        retry_with_backoff(retries, || {
+            self.discord
+                .move_member(&ctx.http, guild_id, user_id, to_channel_id)
+        })
+        .await
+        .context(self.strings.move_users_failed)?;
+        info!("移動後に参加したメンバーを移動: {}", user_id);
+
+        Ok(())
+    }
+
+    /// 移動元のVCのうち、空になったVCを削除する
+    ///
+    /// 移動が反映されるまで一度だけ待ってから、それぞれのVCを確認する
+    async fn cleanup_empty_sources(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        source_channel_ids: &[ChannelId],
+    ) {
+        let source_channel_ids = source_channel_ids
+            .iter()
+            .filter(|channel_id| is_cleanup_target(settings, **channel_id))
+            .collect::<Vec<_>>();
+        if source_channel_ids.is_empty() {
+            return;
+        }
+
+        // 移動が反映されるまですこし待つ
+        tokio::time::sleep(Duration::from_secs(settings.move_wait_seconds)).await;
+
+        for channel_id in source_channel_ids {
+            if let Err(why) = self
+                .cleanup_empty_source(ctx, settings, guild_id, *channel_id)
+                .await
+            {
+                warn!("移動元VCの削除に失敗: {:?}", why);
+            }
+        }
+    }
+
+    /// 移動元のVCが空になっていれば削除する
+    async fn cleanup_empty_source(
+        &self,
+        ctx: &Context,
+        settings: &GuildSettings,
+        guild_id: GuildId,
+        source_channel_id: ChannelId,
+    ) -> Result<()> {
+        // VC作成チャンネルや除外対象のチャンネル、ロビーのVCは削除しない
+        if !is_cleanup_target(settings, source_channel_id) {
+            return Ok(());
+        }
+
+        // VCにまだ誰かいれば削除しない
+        let guild = cached_guild(&*self.discord, ctx, guild_id)
+            .await
+            .context("サーバーの取得に失敗")?;
+        if guild
+            .voice_states
+            .values()
+            .any(|voice_state| voice_state.channel_id == Some(source_channel_id))
+        {
+            return Ok(());
+        }
+
+        // 設定したカテゴリの中のVCのみ削除
+        let channel = self
+            .discord
+            .channel(&ctx.cache, &ctx.http, source_channel_id)
+            .await
+            .context("チャンネルの取得に失敗")?
+            .guild()
+            .context("チャンネルがサーバーのチャンネルではありません")?;
+        if channel.kind != ChannelType::Voice || channel.parent_id != Some(settings.vc_category) {
+            return Ok(());
+        }
+
+        self.discord
+            .delete_channel(&ctx.http, channel.id)
+            .await
+            .context("チャンネルの削除に失敗")?;
+        self.forget_created_channel(guild_id, channel.id).await;
+
+        Ok(())
+    }
+
+    /// メンバーがVCから抜けたとき、/moveで作成したVCが空になっていれば一定時間後に削除する
+    async fn on_voice_state_update(
+        &self,
+        ctx: &Context,
+        old: Option<&VoiceState>,
+        new: &VoiceState,
+    ) -> Result<()> {
+        let grace_seconds = match self.app_config().discord.empty_channel_grace_seconds {
+            Some(grace_seconds) => grace_seconds,
+            None => return Ok(()),
+        };
+
+        // 抜けたチャンネルを取得 (同じチャンネル内での状態の変更は無視)
+        let channel_id = match old.and_then(|old| old.channel_id) {
+            Some(channel_id) if new.channel_id != Some(channel_id) => channel_id,
+            _ => return Ok(()),
+        };
+        let guild_id = match new.guild_id {
+            Some(guild_id) => guild_id,
+            None => return Ok(()),
+        };
+
+        // /moveで作成したVCのみ対象
+        if !self.created_channels.lock().await.contains(&channel_id) {
+            return Ok(());
+        }
+
+        // VCが空になっているか確認
+        let is_empty = |ctx: &Context| -> Result<bool> {
+            let guild = self
+                .discord
+                .guild(&ctx.cache, guild_id)
+                .context("サーバーの取得に失敗")?;
+            Ok(!guild
+                .voice_states
+                .values()
+                .any(|voice_state| voice_state.channel_id == Some(channel_id)))
+        };
+        if !is_empty(ctx)? {
+            return Ok(());
+        }
+
+        // 猶予時間のあいだに誰かが戻ってこなければ削除
+        tokio::time::sleep(Duration::from_secs(grace_seconds)).await;
+        if !is_empty(ctx)? {
+            return Ok(());
+        }
+        // 他の処理ですでに削除されていれば何もしない
+        if !self.created_channels.lock().await.remove(&channel_id) {
+            return Ok(());
+        }
+
+        self.discord
+            .delete_channel(&ctx.http, channel_id)
+            .await
+            .context("空になったVCの削除に失敗")?;
+        self.forget_created_channel(guild_id, channel_id).await;
+        info!("空になったVCを削除しました: {}", channel_id);
+
+        Ok(())
+    }
+}
+
+/// コマンドを登録 (サーバーIDを指定した場合はそのサーバーのみに登録)
+async fn create_command<F>(http: &Http, guild_id: Option<GuildId>, f: F) -> Result<Command>
+where
+    F: FnOnce(&mut CreateApplicationCommand) -> &mut CreateApplicationCommand,
+{
+    let command = match guild_id {
+        Some(guild_id) => guild_id.create_application_command(http, f).await,
+        None => Command::create_global_application_command(http, f).await,
+    };
+    command.context("コマンドの登録に失敗")
+}
+
+/// 登録するコマンドの名前
+const COMMAND_NAMES: &[&str] = &[
+    "move",
+    "move_to",
+    "move_here",
+    "move_random",
+    "move_users",
+    "dismiss",
+    "move_selftest",
+    "move_retarget",
+    "cancel_move",
+    "list_votes",
+    "reload_config",
+    "move_stats",
+    "set_enabled",
+    "cleanup_channels",
+];
+
+/// スレッドの名前の最大文字数
+const THREAD_NAME_MAX_LENGTH: usize = 100;
+
+/// 募集を投稿したスレッドを自動でアーカイブするまでの時間(分)
+const THREAD_AUTO_ARCHIVE_MINUTES: u16 = 60;
+
+/// 移動の統計で集計する期間(日)
+const MOVE_STATS_PERIODS: &[i64] = &[7, 30];
+
+/// 移動の統計で表示するよく募集する人の人数
+const MOVE_STATS_TOP_ORGANIZERS: usize = 5;
+
+/// 再読み込みしても再起動するまで反映されない設定 (起動時やコマンドの登録時にのみ使う)
+const RESTART_REQUIRED_CONFIG: &[&str] = &[
+    "discord.locale",
+    "discord.vote_emoji",
+    "discord.dev_guild_id",
+    "discord.metrics_port",
+    "discord.allow_stage_channels",
+    "discord.message_content_intent",
+    "discord.guild_members_intent",
+    "messages.vote_prompt",
+];
+
+/// 登録済みのコマンドのうち、登録するコマンドにないものを削除 (サーバーIDを指定した場合はそのサーバーのコマンド)
+async fn delete_stale_commands(http: &Http, guild_id: Option<GuildId>) -> Result<()> {
+    let commands = match guild_id {
+        Some(guild_id) => guild_id.get_application_commands(http).await,
+        None => Command::get_global_application_commands(http).await,
+    }
+    .context("登録済みのコマンドの取得に失敗")?;
+
+    for command in commands
+        .iter()
+        .filter(|command| !COMMAND_NAMES.contains(&command.name.as_str()))
+    {
+        let result = match guild_id {
+            Some(guild_id) => guild_id.delete_application_command(http, command.id).await,
+            None => Command::delete_global_application_command(http, command.id).await,
+        };
+        result.context("古いコマンドの削除に失敗")?;
+        warn!(
+            "古いコマンドを削除しました: {} ({})",
+            command.name, command.id
+        );
+    }
+
+    Ok(())
+}
+
+/// Botのオーナー (またはチームのメンバー) か確認
+async fn is_owner(ctx: &Context, user_id: UserId) -> Result<bool> {
+    let application_info = ctx
+        .http
+        .get_current_application_info()
+        .await
+        .context("アプリケーション情報の取得に失敗")?;
+    Ok(application_info.owner.id == user_id
+        || application_info
+            .team
+            .is_some_and(|team| team.members.iter().any(|member| member.user.id == user_id)))
+}
+
+/// サーバーの設定のVC作成チャンネルとカテゴリの誤りを取得
+///
+/// 除外するチャンネルの誤りは募集に影響しないため、ログに出力するのみ
+async fn guild_config_problems(
+    ctx: &Context,
+    guild_id: GuildId,
+    settings: &GuildSettings,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    // VC作成チャンネル (予備を含む) がこのサーバーのボイスチャンネルか確認
+    let create_channels = std::iter::once(("vc_create_channel", settings.vc_create_channel)).chain(
+        settings
+            .vc_create_fallback_channel
+            .map(|channel_id| ("vc_create_fallback_channel", channel_id)),
+    );
+    for (key, channel_id) in create_channels {
+        match channel_id.to_channel(ctx).await {
+            Ok(Channel::Guild(channel))
+                if channel.guild_id == guild_id && channel.kind == ChannelType::Voice => {}
+            Ok(_) => problems.push(format!(
+                "{} ({}) がこのサーバーのボイスチャンネルではありません",
+                key, channel_id
+            )),
+            Err(why) => problems.push(format!(
+                "{} ({}) を取得できません: {}",
+                key, channel_id, why
+            )),
+        }
+    }
+
+    // カテゴリがこのサーバーのカテゴリか確認
+    match settings.vc_category.to_channel(ctx).await {
+        Ok(Channel::Category(category)) if category.guild_id == guild_id => {}
+        Ok(_) => problems.push(format!(
+            "vc_category ({}) がこのサーバーのカテゴリではありません",
+            settings.vc_category
+        )),
+        Err(why) => problems.push(format!(
+            "vc_category ({}) を取得できません: {}",
+            settings.vc_category, why
+        )),
+    }
+
+    // 除外するチャンネルがこのサーバーのチャンネルか確認
+    for channel_id in &settings.vc_ignored_channels {
+        match channel_id.to_channel(ctx).await {
+            Ok(Channel::Guild(channel)) if channel.guild_id == guild_id => {}
+            Ok(_) => warn!(
+                "サーバー {} の vc_ignored_channels ({}) がこのサーバーのチャンネルではありません",
+                guild_id, channel_id
+            ),
+            Err(why) => warn!(
+                "サーバー {} の vc_ignored_channels ({}) を取得できません: {}",
+                guild_id, channel_id, why
+            ),
+        }
+    }
+
+    problems
+}
+
+/// 戻す時刻になったら、移動先VCに残っているメンバーを元のVCへ戻す
+///
+/// 戻す1分前に移動先VCのチャットで予告し (再開したときに予告の時刻を過ぎていれば予告しない)、
+/// 戻す時刻に移動先VCにいないメンバーは戻さない
+async fn return_members(
+    ctx: &Context,
+    discord: &dyn DiscordApi,
+    pending: &PendingReturn,
+    strings: &Strings,
+) {
+    let return_at = tokio::time::Instant::now() + time_until(pending.deadline).unwrap_or_default();
+
+    // 戻す1分前に予告
+    if let Some(wait) = time_until(pending.deadline - 60) {
+        tokio::time::sleep(wait).await;
+        let mut notice = CreateMessage::default();
+        notice.content(
+            strings
+                .return_soon
+                .format(&[pending.source_channel_id.mention()]),
+        );
+        if let Err(why) = discord
+            .send_message(&ctx.http, pending.to_channel_id, notice)
+            .await
+        {
+            warn!("元のVCへ戻る予告に失敗: {:?}", why);
+        }
+    }
+    tokio::time::sleep_until(return_at).await;
+
+    // 移動先VCに残っているメンバーのみ戻す
+    let remaining_user_ids = match cached_guild(discord, ctx, pending.guild_id).await {
+        Some(guild) => pending
+            .user_ids
+            .iter()
+            .copied()
+            .filter(|user_id| {
+                guild
+                    .voice_states
+                    .get(user_id)
+                    .and_then(|voice_state| voice_state.channel_id)
+                    == Some(pending.to_channel_id)
+            })
+            .collect::<Vec<UserId>>(),
+        None => {
+            error!("元のVCへ戻すためのサーバーの取得に失敗");
+            return;
+        }
+    };
+    let mut returned_count = 0;
+    for user_id in remaining_user_ids {
+        match discord
+            .move_member(
+                &ctx.http,
+                pending.guild_id,
+                user_id,
+                pending.source_channel_id,
+            )
+            .await
+        {
+            Ok(_) => returned_count += 1,
+            Err(why) => warn!("メンバーを元のVCへ戻すのに失敗: {:?}", why),
+        }
+    }
+
+    // 結果を送信
+    let mut result = CreateMessage::default();
+    result.content(strings.returned.format(&[
+        returned_count.to_string(),
+        pending.to_channel_id.mention().to_string(),
+        pending.source_channel_id.mention().to_string(),
+    ]));
+    if let Err(why) = discord
+        .send_message(&ctx.http, pending.text_channel_id, result)
+        .await
+    {
+        error!("メッセージの送信に失敗: {:?}", why);
+    }
+}
+
+/// 指定した時刻 (UNIX時間) までの時間 (過ぎていればNone)
+fn time_until(timestamp: i64) -> Option<Duration> {
+    let remaining = timestamp - Local::now().timestamp();
+    (remaining >= 0).then(|| Duration::from_secs(remaining as u64))
+}
+
+/// 元のVCへ戻す予定をファイルに保存 (失敗してもログを出すのみ)
+fn save_returns(vote_store: &VoteStore, returns: &ScheduledReturns) {
+    let pending = returns
+        .values()
+        .map(|(pending, _)| pending)
+        .collect::<Vec<&PendingReturn>>();
+    if let Err(why) = vote_store.save_returns(&pending) {
+        warn!("元のVCへ戻す予定の保存に失敗: {:?}", why);
+    }
+}
+
+/// 進行中の投票をファイルに保存 (失敗してもログを出すのみ)
+fn save_votes(vote_store: &VoteStore, votes: &HashMap<MessageId, Vote>, metrics: &Metrics) {
+    metrics
+        .active_votes
+        .store(votes.len() as u64, Ordering::Relaxed);
+    let stored_votes = votes
+        .iter()
+        .map(|(message_id, vote)| StoredVote::new(*message_id, vote))
+        .collect::<Vec<StoredVote>>();
+    if let Err(why) = vote_store.save(&stored_votes) {
+        warn!("進行中の投票の保存に失敗: {:?}", why);
+    }
+}
+
+/// 参加ボタンのカスタムID
+const VOTE_BUTTON_ID: &str = "move_join";
+
+/// リアクションを追加したユーザーを取得 (1回で取得できるのは100人までのため、最大max人までページングして取得)
+///
+/// 取得したユーザーとAPIの呼び出し回数を返す
+async fn fetch_reaction_users(
+    discord: &dyn DiscordApi,
+    http: &Http,
+    reaction: &Reaction,
+    emoji: &ReactionType,
+    max: usize,
+) -> Result<(Vec<User>, usize)> {
+    let mut users: Vec<User> = Vec::new();
+    let mut requests = 0;
+    while users.len() < max {
+        requests += 1;
+        let after = users.last().map(|user| user.id);
+        let page = discord
+            .reaction_users(
+                http,
+                reaction.channel_id,
+                reaction.message_id,
+                emoji.clone(),
+                100,
+                after,
+            )
+            .await
+            .context("リアクションを追加したユーザーの取得に失敗")?;
+        let is_last_page = page.len() < 100;
+        users.extend(page);
+        if is_last_page {
+            break;
+        }
+    }
+    users.truncate(max);
+    Ok((users, requests))
+}
+
+/// 参加者のうち移動するメンバーを、参加者の順のまま取得
+///
+/// VCにいないメンバーと、すでに移動先にいるメンバー (移動しても何も変わらない) を除き、
+/// excluded を指定した場合 (主催者を移動しない場合) はそのメンバーも除く
+fn move_targets(
+    participants: &[UserId],
+    voice_states: &HashMap<UserId, VoiceState>,
+    to_channel_id: ChannelId,
+    excluded: Option<UserId>,
+) -> Vec<UserId> {
+    participants
+        .iter()
+        .filter(|participant| Some(**participant) != excluded)
+        .filter_map(|participant| voice_states.get(participant))
+        .filter(|voice_state| voice_state.channel_id != Some(to_channel_id))
+        .map(|voice_state| voice_state.user_id)
+        .collect()
+}
+
+/// 移動先のVCの空いている人数までに、移動するメンバーを絞る (移動するメンバー, 満員のため移動しないメンバー)
+///
+/// 主催者の分を先に空け、残りの空きには渡した順 (リアクションを追加した順) に先着順で入れる (移動する順は変えない)
+fn fit_to_capacity(
+    members: Vec<Member>,
+    capacity: Option<usize>,
+    organizer: UserId,
+) -> (Vec<Member>, Vec<Member>) {
+    let Some(capacity) = capacity else {
+        return (members, Vec::new());
+    };
+    let organizer_count = members
+        .iter()
+        .filter(|member| member.user.id == organizer)
+        .count()
+        .min(capacity);
+    let mut remaining = capacity - organizer_count;
+    members.into_iter().partition(|member| {
+        if member.user.id == organizer {
+            return capacity > 0;
+        }
+        if remaining == 0 {
+            return false;
+        }
+        remaining -= 1;
+        true
+    })
+}
+
+/// 集計用の記録に出力するユーザーID
+///
+/// 秘密の文字列を設定した場合は、それを鍵にしたHMAC-SHA256の16進数にする (同じ文字列の間は同じユーザーが同じ値になり、IDには戻せない)
+fn log_user_id(salt: Option<&str>, user_id: UserId) -> String {
+    match salt {
+        Some(salt) => {
+            let key = hmac::Key::new(hmac::HMAC_SHA256, salt.as_bytes());
+            hmac::sign(&key, user_id.0.to_string().as_bytes())
+                .as_ref()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect()
+        }
+        None => user_id.0.to_string(),
+    }
+}
+
+/// 設定で移動対象外にしたメンバーか (ユーザーIDまたはロールで指定する。主催者も対象外にできる)
+fn is_move_exempt(discord: &DiscordConfig, member: &Member) -> bool {
+    discord.move_exempt_user_ids.contains(&member.user.id)
+        || member
+            .roles
+            .iter()
+            .any(|role_id| discord.move_exempt_role_ids.contains(role_id))
+}
+
+/// 移動したメンバーのうち主催者以外の人数 (主催者が移動していない場合や誰も移動していない場合も数えられる)
+fn moved_participant_count(moved_members: &[Member], organizer: UserId) -> usize {
+    moved_members
+        .iter()
+        .filter(|member| member.user.id != organizer)
+        .count()
+}
+
+/// 募集メッセージを削除 (Webhookで投稿した場合はWebhook経由で削除)
+async fn delete_vote_message(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    webhook: Option<&Webhook>,
+    message: &Message,
+) -> serenity::Result<()> {
+    match webhook {
+        Some(webhook) => webhook.delete_message(ctx, message.id).await,
+        None => {
+            discord
+                .delete_message(&ctx.http, message.channel_id, message.id)
+                .await
+        }
+    }
+}
+
+/// 締め切った募集メッセージを削除する (削除しない場合は完了した募集とわかるように編集し、ボタンを外す)
+#[allow(clippy::too_many_arguments)]
+async fn complete_vote_message(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    webhook: Option<&Webhook>,
+    message: &Message,
+    header: &str,
+    destination: &str,
+    strings: &Strings,
+    delete: bool,
+) -> Result<()> {
+    if delete {
+        return delete_vote_message(discord, ctx, webhook, message)
+            .await
+            .context("メッセージの削除に失敗");
+    }
+    let content = format!(
+        "{header}\n\n{}",
+        strings.vote_completed.format(&[destination])
+    );
+    let result = match webhook {
+        Some(webhook) => webhook
+            .edit_message(&ctx, message.id, |m| m.content(content).components(|c| c))
+            .await
+            .map(|_| ()),
+        None => message
+            .channel_id
+            .edit_message(&ctx, message.id, |m| m.content(content).components(|c| c))
+            .await
+            .map(|_| ()),
+    };
+    if let Err(why) = result {
+        warn!("完了した募集メッセージの編集に失敗: {:?}", why);
+    }
+    Ok(())
+}
+
+/// キャッシュからサーバーを取得 (起動直後などでまだキャッシュにない場合は、少し待ってから取得し直す)
+///
+/// VCの参加状態はAPIから取得できないため、キャッシュに揃うのを待つ
+async fn cached_guild(discord: &dyn DiscordApi, ctx: &Context, guild_id: GuildId) -> Option<Guild> {
+    for attempt in 0..=GUILD_CACHE_RETRIES {
+        if let Some(guild) = discord.guild(&ctx.cache, guild_id) {
+            return Some(guild);
+        }
+        if attempt < GUILD_CACHE_RETRIES {
+            debug!(
+                "サーバーがキャッシュにないため、待ってから取得し直します: {} ({}回目)",
+                guild_id,
+                attempt + 1
+            );
+            tokio::time::sleep(GUILD_CACHE_RETRY_DELAY).await;
+        }
+    }
+    None
+}
+
+/// メンバーを取得 (キャッシュになければAPIで取得)
+async fn fetch_member(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> serenity::Result<Member> {
+    let cached = discord
+        .guild(&ctx.cache, guild_id)
+        .and_then(|guild| guild.members.get(&user_id).cloned());
+    match cached {
+        Some(member) => Ok(member),
+        None => discord.member(&ctx.http, guild_id, user_id).await,
+    }
+}
+
+/// チャンネルでのユーザーの権限を求める (キャッシュのサーバーの権限設定から計算する)
+async fn permissions_in(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    channel: &GuildChannel,
+    user_id: UserId,
+) -> Result<Permissions> {
+    let guild = cached_guild(discord, ctx, channel.guild_id)
+        .await
+        .context("サーバーの取得に失敗")?;
+    let member = match guild.members.get(&user_id) {
+        Some(member) => member.clone(),
+        None => discord
+            .member(&ctx.http, channel.guild_id, user_id)
+            .await
+            .context("メンバーの取得に失敗")?,
+    };
+    guild
+        .user_permissions_in(channel, &member)
+        .context("権限の取得に失敗")
+}
+
+/// 起点のメッセージなしで公開スレッドを作成する
+///
+/// serenityのcreate_public_threadはメッセージからスレッドを作成するため使えない。
+/// create_private_threadはメッセージなしでスレッドを作成するエンドポイント (POST /channels/{id}/threads) を呼ぶだけなので、
+/// 種類に公開スレッドを指定して使う (名前はプライベートスレッドだが、作成されるのは公開スレッド)
+async fn create_public_thread_without_message(
+    ctx: &Context,
+    channel_id: ChannelId,
+    name: String,
+) -> serenity::Result<GuildChannel> {
+    channel_id
+        .create_private_thread(ctx, |thread| {
+            thread
+                .name(name)
+                .kind(ChannelType::PublicThread)
+                .auto_archive_duration(THREAD_AUTO_ARCHIVE_MINUTES)
+        })
+        .await
+}
+
+/// 移動先に指定できるチャンネルで、ユーザーがVCに入る権限を持っているか確認
+async fn check_connect_permission(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    channel_types: &[ChannelType],
+    strings: &Strings,
+) -> Result<()> {
+    let channel = discord
+        .channel(&ctx.cache, &ctx.http, channel_id)
+        .await
+        .context(strings.channel_not_found)?
+        .guild()
+        .context("DMチャンネルは取得できません")?;
+    if !channel_types.contains(&channel.kind) {
+        return Err(anyhow!(strings.not_voice_channel));
+    }
+    if !permissions_in(discord, ctx, &channel, user_id)
+        .await?
+        .connect()
+    {
+        return Err(anyhow!(strings.no_connect_permission));
+    }
+    Ok(())
+}
+
+/// 募集の投稿に使うチャンネルで、Botが必要な権限を持っているか確認
+async fn check_bot_permissions(
+    ctx: &Context,
+    channel_id: ChannelId,
+    required: Permissions,
+    strings: &Strings,
+) -> Result<()> {
+    let channel = channel_id
+        .to_channel(&ctx)
+        .await
+        .context(strings.channel_not_found)?
+        .guild()
+        .context("DMチャンネルは取得できません")?;
+    let permissions = match channel.permissions_for_user(ctx, ctx.cache.current_user_id()) {
+        Ok(permissions) => permissions,
+        Err(why) => {
+            // スレッドなどキャッシュから権限を計算できないチャンネルでは確認しない
+            warn!("Botの権限の取得に失敗したため確認を省略します: {why:?}");
+            return Ok(());
+        }
+    };
+    let mut required = required;
+    // 低速モードのチャンネルでは、続けて投稿するために低速モードを無視できる権限が必要
+    if channel.rate_limit_per_user.unwrap_or(0) > 0
+        && !permissions.intersects(Permissions::MANAGE_MESSAGES | Permissions::MANAGE_CHANNELS)
+    {
+        required |= Permissions::MANAGE_MESSAGES;
+    }
+    let missing = required - permissions;
+    if !missing.is_empty() {
+        return Err(anyhow!(strings
+            .bot_missing_permissions
+            .format(&[missing.get_permission_names().join(", ")])));
+    }
+    Ok(())
+}
+
+/// ステージチャンネルであれば、ユーザーを聴衆からスピーカーにする
+async fn promote_on_stage(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Result<()> {
+    let channel = discord
+        .channel(&ctx.cache, &ctx.http, channel_id)
+        .await
+        .context("チャンネルの取得に失敗")?
+        .guild()
+        .context("チャンネルがサーバーのチャンネルではありません")?;
+    // ステージチャンネル以外は何もしない
+    if channel.kind != ChannelType::Stage {
+        return Ok(());
+    }
+
+    // スピーカーにするにはメンバーをミュートする権限が必要
+    let bot_id = discord.current_user_id(&ctx.cache);
+    if !permissions_in(discord, ctx, &channel, bot_id)
+        .await?
+        .mute_members()
+    {
+        return Err(anyhow!("ステージのスピーカーを変更する権限がありません"));
+    }
+
+    discord
+        .unsuppress(&ctx.http, &channel, user_id)
+        .await
+        .context("ボイスステートの変更に失敗")?;
+
+    Ok(())
+}
+
+/// 一時的な失敗の場合に、待ち時間を倍にしながら最大 retries 回再試行する
+async fn retry_with_backoff<T, F, Fut>(retries: u32, mut f: F) -> serenity::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Err(why) if attempt < retries && is_transient_error(&why) => {
+                let delay = retry_delay(attempt);
+                warn!(
+                    "一時的な失敗のため{}ミリ秒後に再試行します ({}/{}): {:?}",
+                    delay.as_millis(),
+                    attempt + 1,
+                    retries,
+                    why
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 再試行するまでの待ち時間 (RETRY_BASE_DELAY から倍にしていき、RETRY_MAX_DELAY で頭打ち)
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// 再試行すれば成功する可能性のあるエラーか (レート制限、Discordのサーバーエラー、通信エラー)
+fn is_transient_error(why: &SerenityError) -> bool {
+    match why {
+        SerenityError::Http(http_error) => match http_error.as_ref() {
+            HttpError::UnsuccessfulRequest(response) => {
+                response.status_code.as_u16() == 429 || response.status_code.is_server_error()
+            }
+            HttpError::Request(_) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// カテゴリのチャンネル数が上限に達していてVCを作成できなかったエラー (返信する文言を持つ)
+#[derive(Debug)]
+struct CategoryFullError(&'static str);
+
+impl std::fmt::Display for CategoryFullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for CategoryFullError {}
+
+/// カテゴリのチャンネル数が上限に達しているために失敗したエラーか
+fn is_category_full_error(why: &SerenityError) -> bool {
+    match why {
+        SerenityError::Http(http_error) => matches!(
+            http_error.as_ref(),
+            HttpError::UnsuccessfulRequest(response)
+                if response.status_code.as_u16() == 400
+                    && response
+                        .error
+                        .errors
+                        .iter()
+                        .any(|error| error.code == "CHANNEL_PARENT_MAX_CHANNELS")
+        ),
+        _ => false,
+    }
+}
+
+/// 権限がないために失敗したエラーか
+fn is_forbidden_error(why: &SerenityError) -> bool {
+    match why {
+        SerenityError::Http(http_error) => matches!(
+            http_error.as_ref(),
+            HttpError::UnsuccessfulRequest(response) if response.status_code.as_u16() == 403
+        ),
+        _ => false,
+    }
+}
+
+/// サーバーがキャッシュにないときに取得し直す回数 (コマンドの応答期限の3秒に収まるようにする)
+const GUILD_CACHE_RETRIES: u32 = 2;
+
+/// サーバーがキャッシュにないときに取得し直すまでの時間
+const GUILD_CACHE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// 再開したときに戻す時刻をこれ以上(秒)過ぎていた、元のVCへ戻す予定は取り消す
+const RETURN_RESUME_GRACE_SECONDS: i64 = 300;
+
+/// 参加予定の人数が変わってから募集メッセージを編集するまでの時間
+const VOTE_EDIT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// 一時的な失敗を再試行するまでの最初の待ち時間
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 一時的な失敗を再試行するまでの最大の待ち時間
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// 募集メッセージにリアクションを付与するときの再試行の回数
+const VOTE_REACTION_RETRIES: u32 = 2;
+
+/// 募集開始の返信を編集するときの再試行の回数 (編集は何度行っても同じ結果になり、二重に返信しないため再試行できる)
+const VOTE_STARTED_REPLY_RETRIES: u32 = 1;
+
+/// 1つのカテゴリに作成できるチャンネルの数
+const CATEGORY_MAX_CHANNELS: usize = 50;
+
+/// VCが作成されたか確認する間隔
+const CHANNEL_CREATE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 同じリアクションのイベントを重複とみなす時間
+const REACTION_DEDUP_WINDOW: Duration = Duration::from_secs(5);
+
+/// チャンネル名の最大文字数 (Discordの制限)
+const CHANNEL_NAME_MAX_LENGTH: usize = 100;
+
+/// 埋め込みの説明の最大文字数 (Discordの制限)
+const EMBED_DESCRIPTION_MAX_LENGTH: usize = 4096;
+
+/// 埋め込みのフィールドの値の最大文字数 (Discordの制限)
+const EMBED_FIELD_VALUE_MAX_LENGTH: usize = 1024;
+
+/// メッセージの本文の最大文字数 (Discordの制限)
+const MESSAGE_CONTENT_MAX_LENGTH: usize = 2000;
+
+/// 移動の理由の最大文字数
+const REASON_MAX_LENGTH: usize = 200;
+
+/// cleanup_channelsで削除するVCとして表示する最大数
+const CLEANUP_PREVIEW_MAX_CHANNELS: usize = 50;
+
+/// 移動後もあとから参加した人を移動する時間の上限(秒)
+const FOLLOW_DURATION_MAX_SECONDS: u64 = 3600;
+
+/// move_randomで分けるVCの最大数
+const MOVE_RANDOM_MAX_ROOMS: u64 = 10;
+
+/// 募集メッセージの形式 (ユーザーに表示する文字列, 募集メッセージ, 募集メッセージの正規表現, ボタンに使う投票の絵文字, 投票に使う絵文字)
+type VoteFormat = (
+    &'static Strings,
+    String,
+    Vec<Regex>,
+    ReactionType,
+    Vec<ReactionType>,
+);
+
+/// 設定から募集メッセージの形式を作成 (起動後に変更すると進行中の募集を読み取れなくなるため、起動時のみ使う)
+fn parse_vote_format(app_config: &AppConfig) -> Result<VoteFormat> {
+    let strings = strings::lookup(&app_config.discord.locale).with_context(|| {
+        format!(
+            "locale の言語 {} には対応していません (対応している言語: {})",
+            app_config.discord.locale,
+            strings::supported_locales().join(", "),
+        )
+    })?;
+    let vote_emojis = app_config
+        .discord
+        .vote_emoji
+        .iter()
+        .map(|emoji| {
+            parse_emoji(emoji)
+                .with_context(|| format!("vote_emoji の絵文字の形式が正しくありません: {}", emoji))
+        })
+        .collect::<Result<Vec<ReactionType>>>()?;
+    let vote_emoji = vote_emojis
+        .first()
+        .cloned()
+        .context("vote_emoji には絵文字を1つ以上指定してください")?;
+    // {emoji} は投票の絵文字 (vote_emojiの最初の絵文字) に置き換える
+    let vote_message = app_config
+        .messages
+        .vote_prompt
+        .as_deref()
+        .unwrap_or(strings.vote_prompt)
+        .replace("{emoji}", &vote_emoji.to_string());
+    // プレースホルダー (主催者, 移動先, 制限時間) がちょうど3つあるか確認
+    let placeholder_count = vote_message.matches("{}").count();
+    if placeholder_count != 3 {
+        return Err(anyhow!(
+            "vote_prompt には {{}} (主催者, 移動先, 制限時間の順) をちょうど3つ含めてください (現在: {}個)",
+            placeholder_count,
+        ));
+    }
+    // 更新前に投稿した以前の募集メッセージも読み取る
+    let vote_message_regexes = std::iter::once(vote_message.as_str())
+        .chain(strings.legacy_vote_prompts.iter().copied())
+        .map(|vote_message| vote_message_regex(vote_message, strings))
+        .collect::<Result<Vec<Regex>>>()?;
+    Ok((
+        strings,
+        vote_message,
+        vote_message_regexes,
+        vote_emoji,
+        vote_emojis,
+    ))
+}
+
+/// 募集メッセージから、主催者と移動先を読み取る正規表現を作成
+fn vote_message_regex(vote_message: &str, strings: &Strings) -> Result<Regex> {
+    let vote_message_escape = regex::escape(&vote_message.replace("{}", "%s")).replace("%s", "{}");
+    let new_channel_regex = regex::escape(strings.new_channel).replace(r"\{\}", r"([^\n]+)");
+    // 制限時間は単位付き (分/秒) と、単位を含まない以前の形式 (数字のみ) のどちらも読み取る
+    let duration_regex = [strings.duration_minutes, strings.duration_seconds]
+        .iter()
+        .map(|format| regex::escape(format).replace(r"\{\}", "[0-9]+"))
+        .chain(std::iter::once("[0-9]+".to_string()))
+        .collect::<Vec<String>>()
+        .join("|");
+    let vote_message_with_regex = vote_message_escape.format(&[
+        r"<@!?([0-9]{1,20})>",
+        &format!(r"(?:<#([0-9]{{1,20}})>|{new_channel_regex})"),
+        &format!("(?:{duration_regex})"),
+    ]);
+    Regex::new(&format!("{vote_message_with_regex}$"))
+        .context("vote_prompt から募集メッセージの正規表現を作成できません")
+}
+
+/// 設定したメッセージに {} がちょうど必要な数だけあるか確認 (起動時と再読み込み時に使う)
+fn validate_message_templates(app_config: &AppConfig) -> Result<()> {
+    let templates = [
+        (
+            "vote_started",
+            &app_config.messages.vote_started,
+            "参加する方法, 移動先",
+            2,
+        ),
+        (
+            "moved_result",
+            &app_config.messages.moved_result,
+            "移動したメンバーの説明, 移動先",
+            2,
+        ),
+    ];
+    for (key, template, placeholders, expected) in templates {
+        let Some(template) = template else {
+            continue;
+        };
+        let count = template.matches("{}").count();
+        if count != expected {
+            return Err(anyhow!(
+                "{} には {{}} ({}の順) をちょうど{}つ含めてください (現在: {}個)",
+                key,
+                placeholders,
+                expected,
+                count,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 移動完了時に結果のメッセージに付与するリアクションを設定から取得
+fn result_ack_emoji(app_config: &AppConfig) -> Result<Option<ReactionType>> {
+    app_config
+        .discord
+        .result_ack_emoji
+        .as_deref()
+        .map(parse_emoji)
+        .transpose()
+        .context("result_ack_emoji の絵文字の形式が正しくありません")
+}
+
+/// 設定の絵文字を読み取る (通常の絵文字か、サーバー絵文字の <:名前:ID> の形式)
+///
+/// ReactionType::try_from は < で始まらない文字列をすべて通常の絵文字として受け付けるため、
+/// `:thumbsup:` や `thumbsup` のような英数字や記号を含む文字列はここで弾く (1️⃣ などのキーキャップの数字と記号は除く)
+fn parse_emoji(emoji: &str) -> Result<ReactionType> {
+    let reaction =
+        ReactionType::try_from(emoji).map_err(|why| anyhow!("絵文字を読み取れません: {}", why))?;
+    if let ReactionType::Unicode(name) = &reaction {
+        let keycap = name.ends_with('\u{20E3}');
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| !c.is_ascii() || (keycap && (c.is_ascii_digit() || c == '#' || c == '*')));
+        if !valid {
+            return Err(anyhow!("通常の絵文字ではありません: {}", name));
+        }
+    }
+    Ok(reaction)
+}
+
+/// 募集の投稿に使うチャンネルでBotに必要な権限を設定から求める
+fn required_permissions(app_config: &AppConfig) -> Permissions {
+    let discord = &app_config.discord;
+    let mut permissions = Permissions::VIEW_CHANNEL
+        | Permissions::SEND_MESSAGES
+        | Permissions::READ_MESSAGE_HISTORY
+        | Permissions::EMBED_LINKS;
+    // リアクションでの投票では、リアクションの追加と参加者のリアクションの削除が必要
+    if discord.vote_with_reaction {
+        permissions |= Permissions::ADD_REACTIONS | Permissions::MANAGE_MESSAGES;
+    }
+    if discord.result_ack_emoji.is_some() {
+        permissions |= Permissions::ADD_REACTIONS;
+    }
+    if discord.use_webhook {
+        permissions |= Permissions::MANAGE_WEBHOOKS;
+    }
+    if discord.use_thread {
+        permissions |= Permissions::CREATE_PUBLIC_THREADS | Permissions::SEND_MESSAGES_IN_THREADS;
+    }
+    permissions
+}
+
+/// 埋め込みのフィールドに並べるメンバーのメンション (1行に1人)
+///
+/// フィールドの値の最大文字数を超える場合は、収まらなかったメンバーを省略して残りの人数を表示する
+fn mention_lines(members: &[Member], and_more: &str) -> String {
+    let lines = members
+        .iter()
+        .map(|member| member.mention().to_string())
+        .collect::<Vec<String>>();
+    let joined = lines.join("\n");
+    if joined.chars().count() <= EMBED_FIELD_VALUE_MAX_LENGTH {
+        return joined;
+    }
+
+    // 残りの人数の表示が収まるまで、末尾のメンバーを省略する
+    let mut shown = lines.len();
+    loop {
+        let omitted = and_more.format(&[lines.len() - shown]);
+        let length = lines[..shown]
+            .iter()
+            .map(|line| line.chars().count() + 1)
+            .sum::<usize>()
+            + omitted.chars().count();
+        if length <= EMBED_FIELD_VALUE_MAX_LENGTH || shown == 0 {
+            return lines[..shown]
+                .iter()
+                .cloned()
+                .chain([omitted])
+                .collect::<Vec<String>>()
+                .join("\n");
+        }
+        shown -= 1;
+    }
+}
+
+/// 空になったときに削除してよい移動元のVCか (VC作成チャンネル、除外対象のチャンネル、ロビーのVCは残す)
+fn is_cleanup_target(settings: &GuildSettings, channel_id: ChannelId) -> bool {
+    !settings.is_create_channel(channel_id)
+        && !settings.vc_ignored_channels.contains(&channel_id)
+        && settings.lobby_channel != Some(channel_id)
+}
+
+/// 最大文字数を超える文字列を切り詰め、末尾を「…」にする
+fn truncate_with_ellipsis(text: String, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text;
+    }
+    text.chars()
+        .take(max_length.saturating_sub(1))
+        .collect::<String>()
+        + "…"
+}
+
+/// テンプレートから作成したVCの名前を生成
+///
+/// {name} を入力したチャンネル名、{organizer} を主催者名に置き換え、
+/// Discordのチャンネル名の長さの制限を超える場合は入力したチャンネル名を切り詰める
+fn render_channel_name(template: &str, name: &str, organizer: &str) -> String {
+    // 入力したチャンネル名以外の部分 (主催者名はここで置き換え、入力に含まれるプレースホルダーは置き換えない)
+    let parts = template
+        .split("{name}")
+        .map(|part| part.replace("{organizer}", organizer))
+        .collect::<Vec<String>>();
+    let fixed_length = parts.iter().map(|part| part.chars().count()).sum::<usize>();
+    let name_count = parts.len() - 1;
+    let name_length = CHANNEL_NAME_MAX_LENGTH
+        .saturating_sub(fixed_length)
+        .checked_div(name_count)
+        .unwrap_or(0);
+    let name = name.chars().take(name_length).collect::<String>();
+
+    // 主催者名が長く入力したチャンネル名を切り詰めても収まらない場合は、全体を切り詰める
+    parts
+        .join(&name)
+        .chars()
+        .take(CHANNEL_NAME_MAX_LENGTH)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// 作成するチャンネル名に許可するパターンを設定から取得
+fn channel_name_pattern(app_config: &AppConfig) -> Result<Option<Regex>> {
+    app_config
+        .discord
+        .channel_name_pattern
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .context("channel_name_pattern の正規表現のコンパイルに失敗")
+}
+
+/// 時間を単位付きの文字列に変換 (分で割り切れる場合は分、それ以外は秒)
+fn duration_text(duration: Duration, strings: &Strings) -> String {
+    match duration.as_secs() {
+        seconds if seconds > 0 && seconds % 60 == 0 => {
+            strings.duration_minutes.format(&[seconds / 60])
+        }
+        seconds => strings.duration_seconds.format(&[seconds]),
+    }
+}
+
+/// 投票の状態から募集メッセージの本文を構築
+fn vote_content(
+    vote: &Vote,
+    vote_message: &str,
+    strings: &Strings,
+    app_config: &AppConfig,
+) -> String {
+    let vote_message = vote_message.format(&[
+        &vote.requester.mention().to_string(),
+        &vote.command_type.to_text(strings),
+        &duration_text(vote.timeout, strings),
+    ]);
+    // リアクションで投票する場合は参加予定の人数を表示 (募集メッセージの正規表現に影響しないよう、募集メッセージより前に置く)
+    let participants = if app_config.discord.vote_with_reaction && vote.participant_count() > 1 {
+        let mut line = strings
+            .participants_count
+            .format(&[vote.participant_count()]);
+        if app_config.discord.show_participants_on_vote {
+            let mentions = vote
+                .reactors
+                .iter()
+                .filter(|id| **id != vote.requester)
+                .map(|id| id.mention().to_string())
+                .collect::<Vec<String>>()
+                .join("");
+            line = format!("{line} {mentions}");
+        }
+        format!("\n{line}")
+    } else {
+        "".to_string()
+    };
+    // まもなく締め切る場合は表示 (参加予定の人数と同じく募集メッセージより前に置く)
+    let closing_soon = if vote.closing_soon {
+        format!("\n{}", strings.closing_soon)
+    } else {
+        "".to_string()
+    };
+    format!(
+        "{}{participants}{closing_soon}\n\n{vote_message}",
+        vote.header
+    )
+}
+
+/// 募集メッセージの本文を編集
+async fn edit_vote_message(
+    discord: &dyn DiscordApi,
+    ctx: &Context,
+    message_id: MessageId,
+    vote: &Vote,
+    content: String,
+) -> Result<()> {
+    discord
+        .edit_message(
+            &ctx.http,
+            vote.webhook.as_ref(),
+            vote.channel_id,
+            message_id,
+            &content,
+        )
+        .await
+        .context("募集メッセージの編集に失敗")
+}
+
+/// 移動の理由を1行に整形し、長さを制限する
+fn sanitize_reason(reason: &str) -> Option<String> {
+    let reason = reason
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .chars()
+        .take(REASON_MAX_LENGTH)
+        .collect::<String>();
+    (!reason.is_empty()).then_some(reason)
+}
+
+/// 名前を指定してコマンドのオプションの値を取得
+fn get_option<'a>(interaction: &'a ApplicationCommandInteraction, name: &str) -> Option<&'a Value> {
+    interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.value.as_ref())
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    /// 準備完了時に呼ばれる
+    async fn ready(&self, ctx: Context, data_about_bot: Ready) {
+        // コマンドを登録 (開発用サーバーIDが設定されていればそのサーバーのみに登録し、すぐに使えるようにする)
+        match self
+            .register_command(&ctx.http, self.app_config().discord.dev_guild_id)
+            .await
+        {
+            Ok(_) => {}
+            Err(why) => {
+                println!("コマンドの登録に失敗しました。: {}", why)
+            }
+        }
+
+        // 設定したチャンネルとカテゴリを確認
+        let guild_ids = data_about_bot
+            .guilds
+            .iter()
+            .map(|guild| guild.id)
+            .collect::<Vec<GuildId>>();
+        self.validate_guild_configs(&ctx, &guild_ids).await;
+
+        // 再起動前の募集を再開
+        if let Err(why) = self.resume_votes(&ctx).await {
+            error!("募集の再開に失敗: {:?}", why);
+        }
+
+        // ログインしたBotの情報を表示
+        warn!("Bot準備完了: {}", data_about_bot.user.tag());
+    }
+
+    /// コマンドの実行やボタンが押されたときに呼ばれる
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        // 不明なインタラクションは無視
+        match interaction {
+            Interaction::ApplicationCommand(interaction) => {
+                let result = self.on_command(&ctx, &interaction).await;
+                // 応答を遅延したか (遅延した応答には新しく応答できないため、編集して返信する)
+                let deferred = self
+                    .deferred_interactions
+                    .lock()
+                    .await
+                    .remove(&interaction.id);
+                if let Err(why) = result {
+                    let sent = if deferred {
+                        interaction
+                            .edit_original_interaction_response(&ctx, |response| {
+                                response.content(why.to_string())
+                            })
+                            .await
+                            .map(|_| ())
+                    } else {
+                        interaction
+                            .create_interaction_response(&ctx, |response| {
+                                response
+                                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                                    .interaction_response_data(|message| {
+                                        message.ephemeral(true);
+                                        message.content(why.to_string());
+                                        message
+                                    })
+                            })
+                            .await
+                    };
+                    if let Err(why) = sent {
+                        error!("エラーメッセージの送信に失敗: {:?}", why);
+                    }
+                }
+            }
+            Interaction::Autocomplete(interaction) => {
+                if let Err(why) = self.on_autocomplete(&ctx, &interaction).await {
+                    warn!("候補の取得に失敗: {:?}", why);
+                }
+            }
+            Interaction::MessageComponent(interaction) => {
+                match self.on_move_button(&ctx, &interaction).await {
+                    Ok(_) => {}
+                    Err(why) => {
+                        match interaction
+                            .create_interaction_response(&ctx, |response| {
+                                response
+                                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                                    .interaction_response_data(|message| {
+                                        message.ephemeral(true);
+                                        message.content(why.to_string());
+                                        message
+                                    })
+                            })
+                            .await
+                        {
+                            Ok(_) => {}
+                            Err(why) => {
+                                error!("エラーメッセージの送信に失敗: {:?}", why);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => return,
+        };
+    }
+
+    /// VCの参加状態が変わったときに呼ばれる
+    async fn voice_state_update(&self, ctx: Context, old: Option<VoiceState>, new: VoiceState) {
+        if let Err(why) = self.on_voice_state_update(&ctx, old.as_ref(), &new).await {
+            error!("空になったVCの処理に失敗: {:?}", why);
+        }
+    }
+
+    /// チャンネルが削除されたときに呼ばれる
+    async fn channel_delete(&self, _ctx: Context, channel: &GuildChannel) {
+        // Bot以外が削除した場合も、作成できるVCの数を戻す
+        self.forget_created_channel(channel.guild_id, channel.id)
+            .await;
+    }
+
+    /// メッセージが削除されたときに呼ばれる
+    async fn message_delete(
+        &self,
+        _ctx: Context,
+        _channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        self.on_vote_message_delete(&[deleted_message_id]).await;
+    }
+
+    /// メッセージがまとめて削除されたときに呼ばれる
+    async fn message_delete_bulk(
+        &self,
+        _ctx: Context,
+        _channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        _guild_id: Option<GuildId>,
+    ) {
+        self.on_vote_message_delete(&multiple_deleted_messages_ids)
+            .await;
+    }
+
+    /// リアクションを外したときに呼ばれる
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        if let Err(why) = self.on_move_reaction_remove(&ctx, &reaction).await {
+            error!("リアクションを外したときの処理に失敗: {:?}", why);
+        }
+    }
+
+    /// リアクションを追加したときに呼ばれる
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        match self.on_move_reaction(&ctx, &reaction).await {
+            Ok(_) => {}
+            Err(why) => {
+                error!("リアクションの反応に失敗: {:?}", why);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::{
+        cache::Cache,
+        client::bridge::gateway::ShardMessenger,
+        model::channel::{PermissionOverwrite, PermissionOverwriteType},
+    };
+
+    use super::*;
+    use crate::discord_api::mock::{self, Call, MockDiscordApi};
+
+    /// テストで使うサーバー
+    const GUILD_ID: GuildId = GuildId(1);
+    /// 募集を開始したVC
+    const SOURCE_CHANNEL_ID: ChannelId = ChannelId(10);
+    /// 移動先のVC
+    const DESTINATION_CHANNEL_ID: ChannelId = ChannelId(20);
+    /// 募集メッセージを投稿したチャンネル
+    const TEXT_CHANNEL_ID: ChannelId = ChannelId(30);
+    /// 募集メッセージ
+    const VOTE_MESSAGE_ID: MessageId = MessageId(40);
+    /// 主催者
+    const ORGANIZER_ID: UserId = UserId(100);
+    /// Bot自身
+    const BOT_ID: UserId = UserId(999);
+
+    /// 初期設定ファイル (bot/config.default.toml) から設定を作成
+    fn test_config() -> AppConfig {
+        config::Config::builder()
+            .add_source(config::File::from_str(
+                include_str!("../bot/config.default.toml"),
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .expect("初期設定の読み込みに失敗")
+    }
+
+    /// テストごとに空の保存先を作成
+    fn test_basedir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!(
+            "discord-vc-vote-move-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("保存先の作成に失敗");
+        dir.to_string_lossy().into_owned()
+    }
+
+    /// Discordへ接続しないコンテキストを作成 (Discordの操作はすべてモックで行う)
+    fn test_context() -> Context {
+        Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger::new(futures::channel::mpsc::unbounded().0),
+            shard_id: 0,
+            http: Arc::new(Http::new("")),
+            cache: Arc::new(Cache::new()),
+        }
+    }
+
+    /// モックでDiscordを操作するハンドラーを作成
+    fn test_handler(app_config: AppConfig, name: &str) -> (Handler, Arc<MockDiscordApi>) {
+        let discord = Arc::new(MockDiscordApi {
+            bot_id: BOT_ID,
+            ..Default::default()
+        });
+        let handler = Handler::new(app_config, &test_basedir(name), discord.clone())
+            .expect("ハンドラーの作成に失敗");
+        (handler, discord)
+    }
+
+    /// 言語を指定した設定を作成
+    fn locale_config(locale: &str) -> AppConfig {
+        let mut app_config = test_config();
+        app_config.discord.locale = locale.to_string();
+        app_config
+    }
+
+    /// テスト用の進行中の投票を作成
+    fn test_vote(command_type: CommandType, reactors: Vec<UserId>) -> Vote {
+        Vote {
+            guild_id: GUILD_ID,
+            channel_id: TEXT_CHANNEL_ID,
+            requester: ORGANIZER_ID,
+            command_type,
+            header: String::new(),
+            source_channel_id: SOURCE_CHANNEL_ID,
+            additional_source_channel_ids: Vec::new(),
+            reactors,
+            reason: None,
+            created_at: Instant::now(),
+            webhook: None,
+            return_after_minutes: None,
+            organizer_room: None,
+            timeout: Duration::from_secs(600),
+            closing_soon: false,
+            limit_to: None,
+            follow_duration: None,
+            triggered: false,
+        }
+    }
+
+    /// 移動先のVCと、通話中のメンバーと、リアクションした順の参加者がいる募集メッセージを用意する
+    ///
+    /// 通話中のユーザーはすべてサーバーのメンバーとし、全員が移動先のVCに入る権限を持つ
+    fn mock_server(
+        discord: &MockDiscordApi,
+        destination: Channel,
+        voice_states: &[(UserId, ChannelId)],
+        reactors: &[UserId],
+    ) {
+        let members = voice_states
+            .iter()
+            .map(|(user_id, _)| mock::member(GUILD_ID, *user_id, &[]))
+            .collect::<Vec<_>>();
+        *discord.guild.lock().unwrap() = Some(mock::guild(
+            GUILD_ID,
+            ORGANIZER_ID,
+            (Permissions::VIEW_CHANNEL | Permissions::CONNECT).bits(),
+            std::slice::from_ref(&destination),
+            &members,
+            voice_states,
+        ));
+        discord
+            .channels
+            .lock()
+            .unwrap()
+            .insert(destination.id(), destination);
+        discord.messages.lock().unwrap().insert(
+            VOTE_MESSAGE_ID,
+            mock::message(VOTE_MESSAGE_ID, TEXT_CHANNEL_ID, BOT_ID, ""),
+        );
+        *discord.reaction_users.lock().unwrap() = reactors
+            .iter()
+            .map(|user_id| mock::user(*user_id, false))
+            .chain([mock::user(BOT_ID, true)])
+            .collect();
+    }
+
+    /// 主催者が募集メッセージに投票の絵文字でリアクションしたイベント
+    fn organizer_reaction() -> Reaction {
+        serde_json::from_value(serde_json::json!({
+            "channel_id": TEXT_CHANNEL_ID.0.to_string(),
+            "message_id": VOTE_MESSAGE_ID.0.to_string(),
+            "guild_id": GUILD_ID.0.to_string(),
+            "user_id": ORGANIZER_ID.0.to_string(),
+            "emoji": { "id": null, "name": "🤚" },
+        }))
+        .unwrap()
+    }
+
+    /// 主催者のリアクションで、リアクションした順に移動先へ移動し、募集メッセージを片付けて結果を送信する
+    #[tokio::test]
+    async fn organizer_reaction_moves_reactors_in_order() {
+        let participant_id = UserId(101);
+        let app_config = test_config();
+
+        // 主催者と参加者が募集を開始したVCにいるサーバー
+        let (handler, discord) = test_handler(app_config, "organizer_reaction");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (participant_id, SOURCE_CHANNEL_ID),
+            ],
+            &[ORGANIZER_ID, participant_id],
+        );
+
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                vec![participant_id],
+            ),
+        );
+
+        // 主催者が投票の絵文字でリアクション
+        handler
+            .on_move_reaction(&test_context(), &organizer_reaction())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (participant_id, DESTINATION_CHANNEL_ID),
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+            ]
+        );
+        let calls = discord.calls();
+        assert!(calls.contains(&Call::DeleteMessage(TEXT_CHANNEL_ID, VOTE_MESSAGE_ID)));
+        assert!(calls
+            .iter()
+            .any(|call| matches!(call, Call::SendMessage(channel_id, _) if *channel_id == TEXT_CHANNEL_ID)));
+        assert!(handler.votes.lock().await.is_empty());
+    }
+
+    /// 移動先のVCに人数制限があれば、空いている人数まで主催者とリアクションした順に先着の参加者だけを移動する
+    #[tokio::test]
+    async fn user_limit_moves_first_reactors() {
+        let occupant_id = UserId(200);
+        let reactor_ids = [UserId(103), UserId(101), UserId(102)];
+        let app_config = test_config();
+
+        // 移動先のVCは3人までで、すでに1人いる (空きは2人)
+        // リアクションはIDの順ではなく、リアクションを追加した順に並べる
+        let (handler, discord) = test_handler(app_config, "user_limit");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 3),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (occupant_id, DESTINATION_CHANNEL_ID),
+                (reactor_ids[0], SOURCE_CHANNEL_ID),
+                (reactor_ids[1], SOURCE_CHANNEL_ID),
+                (reactor_ids[2], SOURCE_CHANNEL_ID),
+            ],
+            &[reactor_ids[0], ORGANIZER_ID, reactor_ids[1], reactor_ids[2]],
+        );
+
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                reactor_ids.to_vec(),
+            ),
+        );
+
+        handler
+            .on_move_reaction(&test_context(), &organizer_reaction())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (reactor_ids[0], DESTINATION_CHANNEL_ID),
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+            ]
+        );
+    }
+
+    /// 募集メッセージを読み取り、作成した投票と同じ主催者と移動先が得られる
+    #[test]
+    fn vote_message_round_trips() {
+        for locale in ["ja", "en"] {
+            let app_config = locale_config(locale);
+            let (handler, _) = test_handler(app_config.clone(), &format!("round_trip_{locale}"));
+            let (strings, vote_message, ..) = parse_vote_format(&app_config).unwrap();
+            let command_types = [
+                CommandType::MoveTo(ChannelId(123456789012345678)),
+                CommandType::MoveTo(ChannelId(u64::MAX)),
+                CommandType::Move("部屋「1」と「2」".to_string()),
+                CommandType::Move("」[VC] 42 \"room\"」".to_string()),
+            ];
+            // 分で表す制限時間と秒で表す制限時間
+            let timeouts = [Duration::from_secs(600), Duration::from_secs(45)];
+            for command_type in command_types {
+                for timeout in timeouts {
+                    let mut vote = test_vote(command_type.clone(), Vec::new());
+                    vote.timeout = timeout;
+                    vote.header = format!("{} 理由: <#1>", ORGANIZER_ID.mention());
+                    let content = vote_content(&vote, &vote_message, strings, &app_config);
+                    assert_eq!(
+                        handler.parse_vote_message(&content).unwrap(),
+                        (ORGANIZER_ID, command_type.clone()),
+                        "{locale}: {content}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// 募集メッセージの形式でない本文は読み取らない
+    #[test]
+    fn vote_message_rejects_other_messages() {
+        let app_config = locale_config("ja");
+        let (handler, _) = test_handler(app_config.clone(), "reject_other");
+        let (strings, vote_message, ..) = parse_vote_format(&app_config).unwrap();
+        let vote = test_vote(CommandType::MoveTo(DESTINATION_CHANNEL_ID), Vec::new());
+        let content = vote_content(&vote, &vote_message, strings, &app_config);
+        // 別の言語の募集メッセージ
+        let en_config = locale_config("en");
+        let (en_strings, en_vote_message, ..) = parse_vote_format(&en_config).unwrap();
+        let en_content = vote_content(&vote, &en_vote_message, en_strings, &en_config);
+
+        for content in [
+            "".to_string(),
+            "こんにちは".to_string(),
+            en_content,
+            // 最後の1文字が欠けた募集メッセージ
+            content.chars().take(content.chars().count() - 1).collect(),
+            // 主催者のメンションがない募集メッセージ
+            content.replace(&ORGANIZER_ID.mention().to_string(), "主催者"),
+            // 移動先がチャンネルのメンションでない募集メッセージ
+            content.replace(&DESTINATION_CHANNEL_ID.mention().to_string(), "#vc"),
+            // 募集メッセージのあとに続きがある本文
+            format!("{content}\n追記"),
+        ] {
+            assert!(handler.parse_vote_message(&content).is_err(), "{content}");
+        }
+    }
+
+    /// 任意の文字列や途中で切れた募集メッセージ、u64に収まらないIDを渡してもパニックせずにエラーを返す
+    #[test]
+    fn vote_message_parse_never_panics() {
+        use rand::{rngs::StdRng, Rng as _, SeedableRng as _};
+
+        let app_config = locale_config("ja");
+        let (handler, _) = test_handler(app_config.clone(), "parse_fuzz");
+        let (strings, vote_message, ..) = parse_vote_format(&app_config).unwrap();
+        let vote = test_vote(CommandType::Move("部屋🔊".to_string()), Vec::new());
+        let content = vote_content(&vote, &vote_message, strings, &app_config);
+
+        // 途中で切れた募集メッセージ (マルチバイト文字の境界ごと)
+        for (index, _) in content.char_indices() {
+            assert!(handler.parse_vote_message(&content[..index]).is_err());
+        }
+
+        // u64に収まらないID
+        let huge_id = "99999999999999999999";
+        for content in [
+            content.replace(
+                &ORGANIZER_ID.mention().to_string(),
+                &format!("<@{huge_id}>"),
+            ),
+            vote_content(
+                &test_vote(CommandType::MoveTo(DESTINATION_CHANNEL_ID), Vec::new()),
+                &vote_message,
+                strings,
+                &app_config,
+            )
+            .replace(
+                &DESTINATION_CHANNEL_ID.mention().to_string(),
+                &format!("<#{huge_id}>"),
+            ),
+        ] {
+            assert!(handler.parse_vote_message(&content).is_err(), "{content}");
+        }
+
+        // 募集メッセージの断片やマルチバイト文字を組み合わせた任意の文字列
+        let pieces = [
+            "<@",
+            "<@!",
+            "<#",
+            ">",
+            "0",
+            "9",
+            huge_id,
+            "新規VC「",
+            "」",
+            "分",
+            "秒",
+            "\n",
+            "🤚",
+            "あ",
+            "\u{200d}",
+            "{}",
+            "$",
+            "(",
+            "\\",
+        ];
+        let mut rng = StdRng::seed_from_u64(203);
+        for _ in 0..2000 {
+            let len = rng.gen_range(0..40);
+            let mut fuzz = (0..len)
+                .map(|_| pieces[rng.gen_range(0..pieces.len())])
+                .collect::<String>();
+            // 途中で切れた募集メッセージのあとに続ける
+            if rng.gen_bool(0.5) {
+                let end = content
+                    .char_indices()
+                    .map(|(index, _)| index)
+                    .nth(rng.gen_range(0..content.chars().count()))
+                    .unwrap();
+                fuzz.insert_str(0, &content[..end]);
+            }
+            assert!(handler.parse_vote_message(&fuzz).is_err(), "{fuzz}");
+        }
+    }
+
+    /// すでに移動先にいるメンバーとVCにいないメンバーは移動せず、参加者の順を保つ
+    #[test]
+    fn move_targets_skip_members_in_destination() {
+        let (in_source, in_destination, elsewhere, not_in_voice) =
+            (UserId(101), UserId(102), UserId(103), UserId(104));
+        let guild = mock::guild(
+            GUILD_ID,
+            ORGANIZER_ID,
+            0,
+            &[],
+            &[],
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (in_source, SOURCE_CHANNEL_ID),
+                (in_destination, DESTINATION_CHANNEL_ID),
+                (elsewhere, ChannelId(11)),
+            ],
+        );
+        let participants = [
+            elsewhere,
+            in_destination,
+            not_in_voice,
+            in_source,
+            ORGANIZER_ID,
+        ];
+
+        assert_eq!(
+            move_targets(
+                &participants,
+                &guild.voice_states,
+                DESTINATION_CHANNEL_ID,
+                None
+            ),
+            vec![elsewhere, in_source, ORGANIZER_ID]
+        );
+        // 主催者を移動しない場合
+        assert_eq!(
+            move_targets(
+                &participants,
+                &guild.voice_states,
+                DESTINATION_CHANNEL_ID,
+                Some(ORGANIZER_ID)
+            ),
+            vec![elsewhere, in_source]
+        );
+        // 全員がすでに移動先にいる場合
+        assert!(move_targets(
+            &[in_destination],
+            &guild.voice_states,
+            DESTINATION_CHANNEL_ID,
+            None
+        )
+        .is_empty());
+    }
+
+    /// 移動した人数は主催者を除いて数え、誰も移動していなくても0になる
+    #[test]
+    fn moved_participant_count_excludes_organizer() {
+        let organizer = mock::member(GUILD_ID, ORGANIZER_ID, &[]);
+        let participant = mock::member(GUILD_ID, UserId(101), &[]);
+
+        assert_eq!(moved_participant_count(&[], ORGANIZER_ID), 0);
+        assert_eq!(
+            moved_participant_count(std::slice::from_ref(&organizer), ORGANIZER_ID),
+            0
+        );
+        // 主催者がVCを抜けていて移動しなかった場合
+        assert_eq!(
+            moved_participant_count(std::slice::from_ref(&participant), ORGANIZER_ID),
+            1
+        );
+        assert_eq!(
+            moved_participant_count(&[participant, organizer], ORGANIZER_ID),
+            1
+        );
+    }
+
+    /// Discordから返ってきたHTTPのエラーを作成
+    fn http_error(status_code: u16) -> SerenityError {
+        SerenityError::Http(Box::new(HttpError::UnsuccessfulRequest(
+            serenity::http::error::ErrorResponse {
+                status_code: serenity::http::StatusCode::from_u16(status_code).unwrap(),
+                url: url::Url::parse("https://discord.com/api/v10").unwrap(),
+                error: serde_json::from_value(serde_json::json!({
+                    "code": 0,
+                    "message": "error",
+                }))
+                .unwrap(),
+            },
+        )))
+    }
+
+    /// 指定した回数だけ一時的な失敗をしてから成功する操作を、再試行して成功させる
+    #[tokio::test(start_paused = true)]
+    async fn retry_with_backoff_succeeds_after_transient_failures() {
+        let failures = 3;
+        let attempts = AtomicUsize::new(0);
+        let result = retry_with_backoff(failures as u32, || async {
+            match attempts.fetch_add(1, Ordering::Relaxed) {
+                attempt if attempt < failures => Err(http_error(503)),
+                attempt => Ok(attempt),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), failures);
+        assert_eq!(attempts.load(Ordering::Relaxed), failures + 1);
+    }
+
+    /// 再試行の回数を使い切ったら、最初の試行と合わせて回数+1回で諦めて最後のエラーを返す
+    #[tokio::test(start_paused = true)]
+    async fn retry_with_backoff_gives_up_after_retries() {
+        let retries = 2;
+        let attempts = AtomicUsize::new(0);
+        let result: serenity::Result<()> = retry_with_backoff(retries, || async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err(http_error(429))
+        })
+        .await;
 
-        // VCのメンバーを取得
-        let voice_member_mentions = guild
-            .voice_states
-            .iter()
-            .filter(|(_, state)| state.channel_id == Some(voice_channel_id))
-            .map(|(id, _)| id.mention().to_string())
-            .collect::<Vec<String>>()
-            .join("");
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), retries as usize + 1);
+    }
 
-        // メッセージを送信
-        let message = interaction
-            .channel_id
-            .send_message(&ctx, |m| {
-                // メッセージを構築
-                let vote_message = self.vote_message.format(&[
-                    &interaction.user.mention().to_string(),
-                    &command_type.to_string(),
-                    &self.app_config.discord.move_timeout_minutes.to_string(),
-                ]);
-                // メッセージを設定
-                m.content(format!(
-                    "{}にいる皆さん({voice_member_mentions})へ{message}\n\n{vote_message}",
-                    voice_channel_id.mention(),
-                ));
-                // メンション可能ロールのみに設定 (@everyone/@hereは禁止)
-                m.allowed_mentions(|a| {
-                    a.parse(ParseValue::Users);
-                    a.roles(
-                        guild
-                            .roles
-                            .values()
-                            .filter(|role| role.mentionable)
-                            .collect::<Vec<_>>(),
-                    );
-                    a
-                });
-                m
+    /// 一時的ではない失敗 (権限がないなど) は再試行しない
+    #[tokio::test(start_paused = true)]
+    async fn retry_with_backoff_does_not_retry_permanent_errors() {
+        for status_code in [400, 403, 404] {
+            let attempts = AtomicUsize::new(0);
+            let result: serenity::Result<()> = retry_with_backoff(5, || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(http_error(status_code))
             })
-            .await
-            .map_err(|_why| anyhow!("メッセージの投稿に失敗しました"))?;
-        // リアクションを付与
-        message
-            .react(&ctx, '🤚')
-            .await
-            .map_err(|_why| anyhow!("リアクションの追加に失敗しました"))?;
+            .await;
 
-        // 一定時間後にメッセージを削除
-        let minutes = self.app_config.discord.move_timeout_minutes;
-        let ctx_clone = ctx.clone();
-        tokio::task::spawn(async move {
-            // minutes分後に削除
-            tokio::time::sleep(std::time::Duration::from_secs(60 * minutes)).await;
+            assert!(result.is_err());
+            assert_eq!(attempts.load(Ordering::Relaxed), 1, "{status_code}");
+        }
+    }
 
-            // メッセージを削除
-            match message.delete(ctx_clone).await {
-                Ok(_) => {}
-                Err(why) => {
-                    error!("メッセージの削除に失敗しました: {}", why);
-                }
-            }
+    /// 再試行までの待ち時間は倍にしていき、RETRY_MAX_DELAY で頭打ちになる
+    #[test]
+    fn retry_delay_is_capped() {
+        assert_eq!(retry_delay(0), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(1), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(2), RETRY_BASE_DELAY * 4);
+        for attempt in [10, 31, 32, 100, u32::MAX] {
+            assert_eq!(retry_delay(attempt), RETRY_MAX_DELAY, "{attempt}");
+        }
+        assert!((0..64).all(|attempt| retry_delay(attempt) <= RETRY_MAX_DELAY));
+    }
+
+    /// リアクションで投票するのがデフォルトで、更新前に投稿した以前の募集メッセージも読み取る
+    #[test]
+    fn legacy_vote_message_is_parsed() {
+        let app_config = test_config();
+        assert!(app_config.discord.vote_with_reaction);
+        let (handler, _) = test_handler(app_config, "legacy_prompt");
+
+        let content = format!(
+            "{}\n\n{}",
+            SOURCE_CHANNEL_ID.mention(),
+            "{}が一緒に移動する人の募集を開始しました。\n{}に移動したい人は{}分以内にリアクション押してください！"
+                .format(&[
+                    ORGANIZER_ID.mention().to_string(),
+                    DESTINATION_CHANNEL_ID.mention().to_string(),
+                    "10".to_string(),
+                ]),
+        );
+        assert_eq!(
+            handler.parse_vote_message(&content).unwrap(),
+            (ORGANIZER_ID, CommandType::MoveTo(DESTINATION_CHANNEL_ID))
+        );
+    }
+
+    /// 元のVCへ戻す予定は保存され、終了時に止めても次回の起動時に再開して戻す
+    #[tokio::test(start_paused = true)]
+    async fn pending_return_is_resumed_after_restart() {
+        let participant_id = UserId(101);
+        let basedir = test_basedir("pending_return");
+        let discord = Arc::new(MockDiscordApi {
+            bot_id: BOT_ID,
+            ..Default::default()
         });
+        *discord.guild.lock().unwrap() = Some(mock::guild(
+            GUILD_ID,
+            ORGANIZER_ID,
+            0,
+            &[],
+            &[],
+            &[
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+                (participant_id, DESTINATION_CHANNEL_ID),
+            ],
+        ));
+        let ctx = test_context();
 
-        // 返信をする
-        interaction
-            .create_interaction_response(&ctx, |response| {
-                response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|message| {
-                        message.ephemeral(true);
-                        message.content(format!("一緒に移動する人の募集を開始しました。\nあなたが🤚をつけると、🤚つけた人と一緒に{command_type}へ移動します。"));
-                        message
-                    })
+        // 戻す予定を開始して保存し、終了時に止める
+        let handler = Handler::new(test_config(), &basedir, discord.clone()).unwrap();
+        handler
+            .arm_return(
+                &ctx,
+                PendingReturn {
+                    message_id: VOTE_MESSAGE_ID,
+                    guild_id: GUILD_ID,
+                    text_channel_id: TEXT_CHANNEL_ID,
+                    to_channel_id: DESTINATION_CHANNEL_ID,
+                    source_channel_id: SOURCE_CHANNEL_ID,
+                    user_ids: vec![participant_id, ORGANIZER_ID],
+                    deadline: Local::now().timestamp() + 120,
+                },
+            )
+            .await;
+        handler.vote_shutdown().shutdown(&ctx.http).await;
+        assert!(handler.returns.lock().await.is_empty());
+        assert_eq!(
+            handler
+                .vote_store
+                .load_returns::<PendingReturn>()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // 再起動後に再開し、戻す時刻になったら戻す
+        let handler = Handler::new(test_config(), &basedir, discord.clone()).unwrap();
+        handler.resume_votes(&ctx).await.unwrap();
+        assert!(handler.returns.lock().await.contains_key(&VOTE_MESSAGE_ID));
+        tokio::time::sleep(Duration::from_secs(180)).await;
+
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (participant_id, SOURCE_CHANNEL_ID),
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+            ]
+        );
+        let sent_channels = discord
+            .calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::SendMessage(channel_id, _) => Some(channel_id),
+                _ => None,
             })
-            .await
-            .map_err(|_why| anyhow!("リアクションの反応に失敗しました"))?;
+            .collect::<Vec<ChannelId>>();
+        assert_eq!(sent_channels, vec![DESTINATION_CHANNEL_ID, TEXT_CHANNEL_ID]);
+        assert!(handler.returns.lock().await.is_empty());
+        assert!(handler
+            .vote_store
+            .load_returns::<PendingReturn>()
+            .unwrap()
+            .is_empty());
+    }
 
-        Ok(())
+    /// 同じチャンネルで同時に募集を開始しても予約できるのは1つだけで、予約を破棄すると (開始に失敗した場合も) また開始できる
+    #[tokio::test]
+    async fn concurrent_votes_reserve_channel_once() {
+        let (handler, _) = test_handler(test_config(), "reserve_vote");
+        let reserve = |interaction_id: u64| {
+            handler.reserve_vote(
+                GUILD_ID,
+                InteractionId(interaction_id),
+                TEXT_CHANNEL_ID,
+                &[SOURCE_CHANNEL_ID],
+            )
+        };
+
+        let (first, second) = tokio::join!(reserve(1), reserve(2));
+        assert!(first.is_ok() != second.is_ok());
+        drop((first, second));
+
+        let reservation = reserve(3).await.unwrap();
+        assert!(reserve(4).await.is_err());
+        drop(reservation);
+        assert!(reserve(5).await.is_ok());
     }
 
-    /// リアクションが押されたときの処理
-    async fn on_move_reaction(&self, ctx: &Context, reaction: &Reaction) -> Result<()> {
-        // リアクションを追加したメッセージを取得
-        let message = reaction
-            .channel_id
-            .message(&ctx, reaction.message_id)
-            .await
-            .context("メッセージの取得に失敗")?;
+    /// 移動先の空いている人数までに、主催者を先に数えてリアクションを追加した順で絞る
+    #[test]
+    fn fit_to_capacity_keeps_reaction_order() {
+        let ids = |members: &[Member]| {
+            members
+                .iter()
+                .map(|member| member.user.id.0)
+                .collect::<Vec<u64>>()
+        };
+        let members = [103, 101, ORGANIZER_ID.0, 102]
+            .map(|id| mock::member(GUILD_ID, UserId(id), &[]))
+            .to_vec();
 
-        // リアクションのメッセージがBotのメッセージでなければ無視
-        if message.author.id != ctx.cache.current_user_id() {
-            return Ok(());
+        let (moving, full) = fit_to_capacity(members.clone(), Some(2), ORGANIZER_ID);
+        assert_eq!(ids(&moving), vec![103, ORGANIZER_ID.0]);
+        assert_eq!(ids(&full), vec![101, 102]);
+
+        // 人数制限がない場合や空きが足りている場合はすべて移動する
+        let (moving, full) = fit_to_capacity(members.clone(), None, ORGANIZER_ID);
+        assert_eq!(ids(&moving), vec![103, 101, ORGANIZER_ID.0, 102]);
+        assert!(full.is_empty());
+        let (moving, full) = fit_to_capacity(members.clone(), Some(10), ORGANIZER_ID);
+        assert_eq!(moving.len(), 4);
+        assert!(full.is_empty());
+
+        // 満員の場合は誰も移動しない
+        let (moving, full) = fit_to_capacity(members, Some(0), ORGANIZER_ID);
+        assert!(moving.is_empty());
+        assert_eq!(full.len(), 4);
+    }
+
+    /// 通常の絵文字とサーバー絵文字は受け付け、名前やショートコードなど絵文字ではない文字列は弾く
+    #[test]
+    fn parse_emoji_rejects_non_emoji() {
+        for emoji in [
+            "🤚",
+            "👍🏽",
+            "1️⃣",
+            "#️⃣",
+            "<:move:123456789012345678>",
+            "<a:move:1>",
+        ] {
+            assert!(parse_emoji(emoji).is_ok(), "{emoji}");
         }
+        for emoji in [
+            "",
+            "thumbsup",
+            ":thumbsup:",
+            "🤚:",
+            "🤚 ",
+            "1",
+            "<move>",
+            "a\u{20E3}",
+        ] {
+            assert!(parse_emoji(emoji).is_err(), "{emoji}");
+        }
+    }
 
-        // メッセージが特定の文字を含んでいなければ無視
-        if !message
-            .content
-            .contains("一緒に移動する人の募集を開始しました")
-        {
-            return Ok(());
+    /// 設定しない場合の募集メッセージは、設定した投票の絵文字を表示する
+    #[test]
+    fn default_vote_prompt_shows_vote_emoji() {
+        for locale in ["ja", "en"] {
+            let mut app_config = locale_config(locale);
+            app_config.discord.vote_emoji = vec!["👍".to_string(), "🤚".to_string()];
+            let (_, vote_message, ..) = parse_vote_format(&app_config).unwrap();
+            assert!(vote_message.contains('👍'), "{vote_message}");
+            assert!(!vote_message.contains('🤚'), "{vote_message}");
+            assert!(!vote_message.contains("{emoji}"), "{vote_message}");
         }
 
-        // リアクションをしたユーザーを取得
-        let user_id = reaction.user_id.context("ユーザーIDの取得に失敗")?;
+        let mut app_config = locale_config("ja");
+        app_config.discord.vote_emoji = vec!["thumbsup".to_string()];
+        assert!(parse_vote_format(&app_config).is_err());
+    }
 
-        // メッセージのメンションユーザーを取得
-        let caps = self
-            .vote_message_regex
-            .captures(&message.content)
-            .context("メッセージのパースに失敗")?;
-        let mention_user = caps
-            .get(1)
-            .and_then(|m| UserId::from_str(m.as_str()).ok())
-            .context("送信者のメンション取得に失敗")?;
+    /// 進行中の投票として記録したメッセージは投稿者に関わらず募集メッセージとみなし、記録にない場合はBotが投稿したメッセージのみ募集メッセージとみなす
+    #[tokio::test]
+    async fn vote_message_is_identified_by_id() {
+        let webhook_id = UserId(555);
+        let (handler, _) = test_handler(test_config(), "is_vote_message");
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(CommandType::MoveHere(DESTINATION_CHANNEL_ID), vec![]),
+        );
 
-        // リアクションを追加した人がメンションされた人でなければ無視
-        if mention_user != user_id {
-            return Ok(());
-        }
+        // Webhookで投稿した募集メッセージ
+        let webhook_message = mock::message(VOTE_MESSAGE_ID, TEXT_CHANNEL_ID, webhook_id, "");
+        assert!(handler.is_vote_message(&webhook_message, BOT_ID).await);
 
-        // メッセージのメンションチャンネルを取得
-        let mention_channel_id = CommandType::parse(caps.get(2), caps.get(3))
-            .context("移動先VCのチャンネル取得に失敗")?;
+        // 記録にないメッセージはBotが投稿したもののみ
+        let other_id = MessageId(41);
+        let bot_message = mock::message(other_id, TEXT_CHANNEL_ID, BOT_ID, "");
+        assert!(handler.is_vote_message(&bot_message, BOT_ID).await);
+        let webhook_message = mock::message(other_id, TEXT_CHANNEL_ID, webhook_id, "");
+        assert!(!handler.is_vote_message(&webhook_message, BOT_ID).await);
+        let user_message = mock::message(other_id, TEXT_CHANNEL_ID, ORGANIZER_ID, "");
+        assert!(!handler.is_vote_message(&user_message, BOT_ID).await);
+    }
 
-        // リアクションを追加した人がボイスチャンネルにいるか確認
-        let guild_id = reaction.guild_id.context("サーバーの取得に失敗")?;
-        let guild = guild_id
-            .to_guild_cached(&ctx)
-            .context("サーバーの取得に失敗")?;
-        let voice_state = guild
-            .voice_states
-            .get(&user_id)
-            .context("ボイスチャンネルに参加していません")?;
-        let _voice_channel_id = voice_state
-            .channel_id
-            .context("ボイスチャンネルのIDの取得に失敗")?;
+    /// Bot自身のリアクションは無視する
+    #[tokio::test]
+    async fn bot_reaction_is_ignored() {
+        let (handler, discord) = test_handler(test_config(), "bot_reaction");
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(CommandType::MoveHere(DESTINATION_CHANNEL_ID), vec![]),
+        );
+        let mut reaction = organizer_reaction();
+        reaction.user_id = Some(BOT_ID);
 
-        // リアクションを追加した人リストを取得
-        let reaction_users = reaction
-            .users(&ctx, '🤚', None, None::<UserId>)
+        handler
+            .on_move_reaction(&test_context(), &reaction)
             .await
-            .context("リアクションを追加したユーザーの取得に失敗")?
+            .unwrap();
+
+        assert!(discord.calls().is_empty());
+        assert!(handler.votes.lock().await[&VOTE_MESSAGE_ID]
+            .reactors
+            .is_empty());
+    }
+
+    /// 募集の投稿に必要な権限は、使う機能の設定に応じて増える
+    #[test]
+    fn required_permissions_follow_config() {
+        let base = Permissions::VIEW_CHANNEL
+            | Permissions::SEND_MESSAGES
+            | Permissions::READ_MESSAGE_HISTORY
+            | Permissions::EMBED_LINKS;
+        let mut app_config = test_config();
+        app_config.discord.vote_with_reaction = false;
+        app_config.discord.use_webhook = false;
+        app_config.discord.use_thread = false;
+        app_config.discord.result_ack_emoji = None;
+        assert_eq!(required_permissions(&app_config), base);
+
+        let mut reaction = app_config.clone();
+        reaction.discord.vote_with_reaction = true;
+        assert_eq!(
+            required_permissions(&reaction),
+            base | Permissions::ADD_REACTIONS | Permissions::MANAGE_MESSAGES
+        );
+
+        let mut ack = app_config.clone();
+        ack.discord.result_ack_emoji = Some("✅".to_string());
+        assert_eq!(
+            required_permissions(&ack),
+            base | Permissions::ADD_REACTIONS
+        );
+
+        let mut webhook = app_config.clone();
+        webhook.discord.use_webhook = true;
+        assert_eq!(
+            required_permissions(&webhook),
+            base | Permissions::MANAGE_WEBHOOKS
+        );
+
+        let mut thread = app_config.clone();
+        thread.discord.use_thread = true;
+        assert_eq!(
+            required_permissions(&thread),
+            base | Permissions::CREATE_PUBLIC_THREADS | Permissions::SEND_MESSAGES_IN_THREADS
+        );
+    }
+
+    /// ユーザーIDかロールで指定したメンバーは移動対象外になり、主催者も対象外にできる
+    #[test]
+    fn move_exempt_by_user_id_or_role() {
+        let mut discord = test_config().discord;
+        discord.move_exempt_user_ids = vec![UserId(101), ORGANIZER_ID];
+        discord.move_exempt_role_ids = vec![RoleId(500)];
+
+        assert!(is_move_exempt(
+            &discord,
+            &mock::member(GUILD_ID, UserId(101), &[])
+        ));
+        assert!(is_move_exempt(
+            &discord,
+            &mock::member(GUILD_ID, UserId(102), &[400, 500])
+        ));
+        assert!(is_move_exempt(
+            &discord,
+            &mock::member(GUILD_ID, ORGANIZER_ID, &[])
+        ));
+        assert!(!is_move_exempt(
+            &discord,
+            &mock::member(GUILD_ID, UserId(103), &[400])
+        ));
+
+        // 指定しなければ誰も対象外にならない
+        let discord = test_config().discord;
+        assert!(!is_move_exempt(
+            &discord,
+            &mock::member(GUILD_ID, ORGANIZER_ID, &[500])
+        ));
+    }
+
+    /// 同じ投票を同時に締め切っても、締め切りを開始できるのは1回だけで、メンバーは1回だけ移動する
+    #[tokio::test]
+    async fn concurrent_triggers_move_once() {
+        let participant_id = UserId(101);
+        let (handler, discord) = test_handler(test_config(), "concurrent_trigger");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (participant_id, SOURCE_CHANNEL_ID),
+            ],
+            &[ORGANIZER_ID, participant_id],
+        );
+        let command_type = CommandType::MoveHere(DESTINATION_CHANNEL_ID);
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(command_type.clone(), vec![participant_id]),
+        );
+
+        // 締め切りの記録だけを同時に行う
+        let (first, second) = tokio::join!(
+            handler.claim_trigger(VOTE_MESSAGE_ID),
+            handler.claim_trigger(VOTE_MESSAGE_ID)
+        );
+        assert!(first ^ second);
+        handler.release_trigger(VOTE_MESSAGE_ID).await;
+
+        // 移動まで同時に行う
+        let ctx = test_context();
+        let message = discord.messages.lock().unwrap()[&VOTE_MESSAGE_ID].clone();
+        let participants = vec![ORGANIZER_ID, participant_id];
+        let (first, second) = tokio::join!(
+            handler.trigger_move(
+                &ctx,
+                GUILD_ID,
+                &message,
+                ORGANIZER_ID,
+                command_type.clone(),
+                participants.clone(),
+            ),
+            handler.trigger_move(
+                &ctx,
+                GUILD_ID,
+                &message,
+                ORGANIZER_ID,
+                command_type.clone(),
+                participants.clone(),
+            ),
+        );
+        first.unwrap();
+        second.unwrap();
+
+        let mut moves = discord.moves();
+        moves.sort();
+        assert_eq!(
+            moves,
+            vec![
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+                (participant_id, DESTINATION_CHANNEL_ID),
+            ]
+        );
+        let results = discord
+            .calls()
             .into_iter()
-            .filter(|user| user.id != ctx.cache.current_user_id())
-            .collect::<Vec<User>>();
+            .filter(|call| matches!(call, Call::SendMessage(channel_id, _) if *channel_id == TEXT_CHANNEL_ID))
+            .count();
+        assert_eq!(results, 1);
+    }
 
-        // 移動先チャンネルを取得/作成
-        let to_channel_id = match mention_channel_id {
-            CommandType::MoveTo(channel_id) => {
-                // 権限を確認
-                let channel = channel_id
-                    .to_channel(&ctx)
-                    .await
-                    .context("チャンネルが取得できません")?
-                    .guild()
-                    .context("DMチャンネルは取得できません")?;
-                if !channel
-                    .permissions_for_user(&ctx, user_id)
-                    .context("権限の取得に失敗")?
-                    .connect()
-                {
-                    return Err(anyhow!("指定されたVCに入る権限がありません"));
-                }
+    /// チャンネル名は文字数 (バイト数ではない) で100文字まで受け付け、制御文字を含む名前は受け付けない
+    #[test]
+    fn channel_name_length_boundary() {
+        let (handler, _) = test_handler(test_config(), "channel_name_length");
+        for c in ["a", "部", "🔊"] {
+            assert!(handler.validate_channel_name(&c.repeat(99)).is_ok(), "{c}");
+            assert!(handler.validate_channel_name(&c.repeat(100)).is_ok(), "{c}");
+            assert!(
+                handler.validate_channel_name(&c.repeat(101)).is_err(),
+                "{c}"
+            );
+        }
+        for name in ["移動\n部屋", "移動\t部屋", "移動\u{0}部屋"] {
+            assert!(handler.validate_channel_name(name).is_err(), "{name:?}");
+        }
+    }
 
-                channel_id
+    /// テンプレートを適用したVCの名前は100文字に収まるよう、入力したチャンネル名の部分だけを切り詰める
+    #[test]
+    fn render_channel_name_truncates_at_limit() {
+        // テンプレートの固定部分は2文字 (「🔊 」)
+        for c in ["a", "部"] {
+            for (length, expected) in [(97, 97), (98, 98), (99, 98)] {
+                let rendered = render_channel_name("🔊 {name}", &c.repeat(length), "主催者");
+                assert_eq!(rendered, format!("🔊 {}", c.repeat(expected)));
+                assert!(rendered.chars().count() <= CHANNEL_NAME_MAX_LENGTH);
             }
-            CommandType::Move(channel_name) => {
-                // メンバーを取得
-                let member = guild
-                    .member(&ctx, user_id)
-                    .await
-                    .context("メンバーの取得に失敗")?;
+        }
 
-                // まず一人移動
-                member
-                    .move_to_voice_channel(&ctx, &self.app_config.discord.vc_create_channel)
-                    .await
-                    .context("移動に失敗")?;
+        // {organizer} は主催者名に置き換え、主催者名の長さも含めて100文字に収める
+        let template = "{organizer}の部屋: {name}";
+        let fixed_length = "主催者の部屋: ".chars().count();
+        for length in [99, 100, 101] {
+            let name = "部".repeat(length - fixed_length);
+            let rendered = render_channel_name(template, &name, "主催者");
+            let expected_length = length.min(CHANNEL_NAME_MAX_LENGTH) - fixed_length;
+            assert_eq!(
+                rendered,
+                format!("主催者の部屋: {}", "部".repeat(expected_length))
+            );
+        }
 
-                // すこし待つ
-                tokio::time::sleep(std::time::Duration::from_secs(
-                    self.app_config.discord.move_wait_seconds,
-                ))
-                .await;
+        // 入力したチャンネル名に含まれるプレースホルダーは置き換えない
+        assert_eq!(
+            render_channel_name(template, "{organizer}", "主催者"),
+            "主催者の部屋: {organizer}"
+        );
 
-                // VCの状態が変わっているため、ギルドを再取得
-                let guild = guild_id
-                    .to_guild_cached(&ctx)
-                    .context("サーバーの取得に失敗")?;
+        // 主催者名だけで100文字を超える場合は全体を切り詰める
+        let organizer = "主".repeat(120);
+        let rendered = render_channel_name(template, "部屋", &organizer);
+        assert_eq!(rendered, "主".repeat(CHANNEL_NAME_MAX_LENGTH));
+    }
 
-                // メンバーが移動した先のチャンネルを取得
-                let voice_state = guild
-                    .voice_states
-                    .get(&user_id)
-                    .context("ボイスチャンネルに参加していません")?;
-                let voice_channel_id = voice_state
-                    .channel_id
-                    .context("ボイスチャンネルのIDの取得に失敗")?;
+    /// 許可するパターンに一致し、禁止されている単語 (大文字と小文字は区別しない) を含まない名前のみ受け付ける
+    #[test]
+    fn channel_name_allowed_and_blocked() {
+        let mut app_config = test_config();
+        app_config.discord.channel_name_pattern =
+            Some(r"^[\p{Han}\p{Hiragana}a-zA-Z0-9 ]+$".to_string());
+        app_config.discord.channel_name_blocklist = vec!["badword".to_string()];
+        let (handler, _) = test_handler(app_config, "channel_name_moderation");
+        let strings = handler.strings;
 
-                // 除外対象か確認
-                if self
-                    .app_config
-                    .discord
-                    .vc_ignored_channels
-                    .contains(&voice_channel_id)
-                {
-                    return Err(anyhow!("除外対象のチャンネルです"));
-                }
+        for name in ["移動部屋", "game room 2", "あそぶ"] {
+            assert!(handler.validate_channel_name(name).is_ok(), "{name}");
+        }
+        for name in ["移動部屋!", "🔊 部屋", "ルーム"] {
+            let error = handler.validate_channel_name(name).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                strings.channel_name_not_allowed,
+                "{name}"
+            );
+        }
+        for name in ["badword", "my BadWord room"] {
+            let error = handler.validate_channel_name(name).unwrap_err();
+            assert_eq!(error.to_string(), strings.channel_name_blocked, "{name}");
+        }
+    }
 
-                // チャンネルを取得
-                let mut channel = voice_channel_id
-                    .to_channel(&ctx)
-                    .await
-                    .context("チャンネルの取得に失敗")?
-                    .guild()
-                    .context("チャンネルがサーバーのチャンネルではありません")?;
+    /// すでに移動先のVCにいる参加者は移動せず、移動した人数にも数えない
+    #[tokio::test]
+    async fn members_in_destination_are_not_moved_or_counted() {
+        let participant_id = UserId(101);
+        let present_id = UserId(102);
+        let (handler, discord) = test_handler(test_config(), "already_in_destination");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (participant_id, SOURCE_CHANNEL_ID),
+                (present_id, DESTINATION_CHANNEL_ID),
+            ],
+            &[ORGANIZER_ID, present_id, participant_id],
+        );
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                vec![present_id, participant_id],
+            ),
+        );
 
-                // 設定したカテゴリの中か確認
-                if channel.parent_id != Some(self.app_config.discord.vc_category) {
-                    return Err(anyhow!("カテゴリが違います"));
+        handler
+            .on_move_reaction(&test_context(), &organizer_reaction())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (participant_id, DESTINATION_CHANNEL_ID),
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+            ]
+        );
+        let result = discord
+            .calls()
+            .into_iter()
+            .find_map(|call| match call {
+                Call::SendMessage(channel_id, content) if channel_id == TEXT_CHANNEL_ID => {
+                    Some(content)
                 }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            result,
+            format!(
+                "{}と一緒に1人のメンバーを{}へ移動しました。",
+                ORGANIZER_ID.mention(),
+                DESTINATION_CHANNEL_ID.mention()
+            )
+        );
+    }
 
-                // VCの名前を変更
-                channel
-                    .edit(&ctx, |c| c.name(channel_name))
-                    .await
-                    .context("チャンネルの名前の変更に失敗")?;
+    /// 移動先のVCに入る権限はメンバーごとに確認し、権限がないメンバーだけを移動しない
+    #[tokio::test]
+    async fn members_without_connect_permission_are_not_moved() {
+        let allowed_id = UserId(101);
+        let denied_id = UserId(102);
+        let denied_role_id = UserId(103);
+        let (handler, discord) = test_handler(test_config(), "mixed_permissions");
 
-                voice_channel_id
-            }
-        };
+        // ユーザーとロールでVCに入る権限を拒否した移動先
+        let mut destination = mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0);
+        if let Channel::Guild(channel) = &mut destination {
+            channel.permission_overwrites = vec![
+                PermissionOverwrite {
+                    allow: Permissions::empty(),
+                    deny: Permissions::CONNECT,
+                    kind: PermissionOverwriteType::Member(denied_id),
+                },
+                PermissionOverwrite {
+                    allow: Permissions::empty(),
+                    deny: Permissions::CONNECT,
+                    kind: PermissionOverwriteType::Role(RoleId(500)),
+                },
+            ];
+        }
+        mock_server(
+            &discord,
+            destination,
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (allowed_id, SOURCE_CHANNEL_ID),
+                (denied_id, SOURCE_CHANNEL_ID),
+                (denied_role_id, SOURCE_CHANNEL_ID),
+            ],
+            &[ORGANIZER_ID, denied_id, allowed_id, denied_role_id],
+        );
+        if let Some(guild) = discord.guild.lock().unwrap().as_mut() {
+            guild.roles.insert(
+                RoleId(500),
+                serde_json::from_value(serde_json::json!({
+                    "id": "500",
+                    "guild_id": GUILD_ID.0.to_string(),
+                    "name": "denied",
+                    "color": 0,
+                    "hoist": false,
+                    "managed": false,
+                    "mentionable": false,
+                    "permissions": "0",
+                    "position": 1,
+                }))
+                .unwrap(),
+            );
+            guild.members.insert(
+                denied_role_id,
+                mock::member(GUILD_ID, denied_role_id, &[500]),
+            );
+        }
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                vec![denied_id, allowed_id, denied_role_id],
+            ),
+        );
 
-        // リアクションをした人全員をボイスチャンネルに移動
-        let members = try_join_all(
-            reaction_users
-                .iter()
-                // 通話状態を取得
-                .filter_map(|user| guild.voice_states.get(&user.id))
-                // メンバーを取得
-                .map(|voice_state| guild.member(&ctx, voice_state.user_id)),
-        )
-        .await?;
+        handler
+            .on_move_reaction(&test_context(), &organizer_reaction())
+            .await
+            .unwrap();
 
-        // メンバーを移動
-        for member in &members {
-            // リアクションを追加した人がボイスチャンネルにいる場合は移動
-            let _ = member.move_to_voice_channel(&ctx, to_channel_id).await;
-        }
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (allowed_id, DESTINATION_CHANNEL_ID),
+                (ORGANIZER_ID, DESTINATION_CHANNEL_ID),
+            ]
+        );
+    }
+
+    /// VC作成チャンネルが満員で移動できなければ予備のVC作成チャンネルでVCを作成し、両方とも失敗した場合のみエラーにする
+    #[tokio::test]
+    async fn full_create_channel_falls_back() {
+        let create_channel_id = ChannelId(50);
+        let fallback_channel_id = ChannelId(51);
+        let created_channel_id = ChannelId(60);
+        let mut app_config = test_config();
+        app_config.discord.vc_create_channel = Some(create_channel_id);
+        app_config.discord.vc_create_fallback_channel = Some(fallback_channel_id);
+        let settings = app_config.guild(GUILD_ID).unwrap();
+        let (handler, discord) = test_handler(app_config, "create_fallback");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[(ORGANIZER_ID, SOURCE_CHANNEL_ID)],
+            &[],
+        );
+        discord
+            .full_channels
+            .lock()
+            .unwrap()
+            .insert(create_channel_id);
+        discord
+            .created_channels
+            .lock()
+            .unwrap()
+            .insert(fallback_channel_id, created_channel_id);
+        let ctx = test_context();
 
-        // 募集のメッセージを削除
-        message
-            .delete(&ctx)
+        let channel_id = handler
+            .create_channel_for(&ctx, &settings, GUILD_ID, ORGANIZER_ID)
             .await
-            .context("メッセージの削除に失敗")?;
-        // 結果を送信
-        reaction
-            .channel_id
-            .send_message(&ctx, |message| {
-                message.content(format!(
-                    "{}と一緒に{}人のメンバーを{}へ移動しました。",
-                    mention_user.mention(),
-                    members.len() - 1,
-                    to_channel_id.mention(),
-                ));
-                message.embed(|embed| {
-                    embed.title("移動したメンバー");
-                    embed.description(
-                        members
-                            .iter()
-                            .map(|member| member.mention().to_string())
-                            .collect::<Vec<String>>()
-                            .join("\n"),
-                    );
-                    embed
-                });
-                message
+            .unwrap();
+        assert_eq!(channel_id, created_channel_id);
+        assert_eq!(
+            discord.moves(),
+            vec![
+                (ORGANIZER_ID, create_channel_id),
+                (ORGANIZER_ID, fallback_channel_id),
+            ]
+        );
+
+        // 予備のVC作成チャンネルも満員ならエラー
+        discord
+            .full_channels
+            .lock()
+            .unwrap()
+            .insert(fallback_channel_id);
+        assert!(handler
+            .create_channel_for(&ctx, &settings, GUILD_ID, ORGANIZER_ID)
+            .await
+            .is_err());
+    }
+
+    /// 秘密の文字列を設定すると、ユーザーIDは同じ文字列の間は同じ値のハッシュになり、元のIDを含まない
+    #[test]
+    fn log_user_id_is_redacted_with_salt() {
+        let user_id = UserId(123456789012345678);
+        assert_eq!(log_user_id(None, user_id), "123456789012345678");
+
+        let hashed = log_user_id(Some("salt"), user_id);
+        assert_eq!(hashed.len(), 64);
+        assert!(hashed.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(!hashed.contains("123456789012345678"));
+        assert_eq!(log_user_id(Some("salt"), user_id), hashed);
+        assert_ne!(log_user_id(Some("other"), user_id), hashed);
+        assert_ne!(log_user_id(Some("salt"), UserId(1)), hashed);
+    }
+
+    /// 移動したメンバーへのDMはDiscordApiを通して移動したメンバー全員に送信する
+    #[tokio::test]
+    async fn dm_on_move_sends_dm_to_moved_members() {
+        let participant_id = UserId(101);
+        let mut app_config = test_config();
+        app_config.discord.dm_on_move = true;
+        let (handler, discord) = test_handler(app_config, "dm_on_move");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (participant_id, SOURCE_CHANNEL_ID),
+            ],
+            &[ORGANIZER_ID, participant_id],
+        );
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                vec![participant_id],
+            ),
+        );
+
+        handler
+            .on_move_reaction(&test_context(), &organizer_reaction())
+            .await
+            .unwrap();
+
+        let dms = discord
+            .calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::SendDm(user_id, _) => Some(user_id),
+                _ => None,
             })
+            .collect::<Vec<_>>();
+        assert_eq!(dms, vec![participant_id, ORGANIZER_ID]);
+    }
+
+    /// カテゴリがいっぱいでVCを作成できなければその旨を返し、設定されていればVC作成チャンネルで作成する
+    #[tokio::test]
+    async fn category_full_falls_back_to_create_channel() {
+        let created_channel_id = ChannelId(60);
+        let mut app_config = test_config();
+        app_config.discord.create_channel_explicitly = true;
+        let settings = app_config.guild(GUILD_ID).unwrap();
+        let ctx = test_context();
+
+        // 設定されていなければ、カテゴリがいっぱいであることを返す
+        let (handler, discord) = test_handler(app_config.clone(), "category_full");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[(ORGANIZER_ID, SOURCE_CHANNEL_ID)],
+            &[],
+        );
+        discord
+            .full_categories
+            .lock()
+            .unwrap()
+            .insert(settings.vc_category);
+        let why = handler
+            .move_organizer_on_start(&ctx, &settings, GUILD_ID, ORGANIZER_ID, "room".into())
             .await
-            .context("メッセージの送信に失敗")?;
+            .unwrap_err();
+        assert_eq!(why.to_string(), handler.strings.category_full);
+        assert_eq!(discord.calls(), vec![Call::CreateChannel("room".into())]);
 
-        Ok(())
+        // 設定されていれば、VC作成チャンネルへ移動して作成されたVCを使う
+        app_config.discord.category_full_fallback = true;
+        let (handler, discord) = test_handler(app_config, "category_full_fallback");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[(ORGANIZER_ID, SOURCE_CHANNEL_ID)],
+            &[],
+        );
+        discord
+            .full_categories
+            .lock()
+            .unwrap()
+            .insert(settings.vc_category);
+        discord
+            .created_channels
+            .lock()
+            .unwrap()
+            .insert(settings.vc_create_channel, created_channel_id);
+        let channel_id = handler
+            .move_organizer_on_start(&ctx, &settings, GUILD_ID, ORGANIZER_ID, "room".into())
+            .await
+            .unwrap();
+        assert_eq!(channel_id, created_channel_id);
+        assert_eq!(
+            discord.moves(),
+            vec![(ORGANIZER_ID, settings.vc_create_channel)]
+        );
     }
-}
 
-#[async_trait]
-impl EventHandler for Handler {
-    /// 準備完了時に呼ばれる
-    async fn ready(&self, ctx: Context, data_about_bot: Ready) {
-        // コマンドを登録
-        match self.register_command(&ctx).await {
-            Ok(_) => {}
-            Err(why) => {
-                println!("コマンドの登録に失敗しました。: {}", why)
-            }
-        }
+    /// Botが作成したVCの数が上限に達していれば作成せず、作成したVCを削除すると再び作成できる
+    #[tokio::test(start_paused = true)]
+    async fn created_channels_are_capped_per_guild() {
+        let mut app_config = test_config();
+        app_config.discord.create_channel_explicitly = true;
+        app_config.discord.max_created_channels_per_guild = Some(1);
+        let settings = app_config.guild(GUILD_ID).unwrap();
+        let (handler, discord) = test_handler(app_config, "created_channel_limit");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[(ORGANIZER_ID, SOURCE_CHANNEL_ID)],
+            &[],
+        );
+        let ctx = test_context();
 
-        // ログインしたBotの情報を表示
-        warn!("Bot準備完了: {}", data_about_bot.user.tag());
+        let channel_id = handler
+            .move_organizer_on_start(&ctx, &settings, GUILD_ID, ORGANIZER_ID, "room".into())
+            .await
+            .unwrap();
+
+        // 上限に達していれば作成しない
+        let why = handler
+            .move_organizer_on_start(&ctx, &settings, GUILD_ID, ORGANIZER_ID, "room".into())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            why.to_string(),
+            handler.strings.created_channel_limit.format(&[1])
+        );
+        assert_eq!(
+            discord
+                .calls()
+                .iter()
+                .filter(|call| matches!(call, Call::CreateChannel(_)))
+                .count(),
+            1
+        );
+
+        // 作成したVCが空になって削除されれば、再び作成できる
+        discord
+            .move_member(&ctx.http, GUILD_ID, ORGANIZER_ID, SOURCE_CHANNEL_ID)
+            .await
+            .unwrap();
+        handler
+            .cleanup_empty_source(&ctx, &settings, GUILD_ID, channel_id)
+            .await
+            .unwrap();
+        assert!(discord.calls().contains(&Call::DeleteChannel(channel_id)));
+        handler
+            .move_organizer_on_start(&ctx, &settings, GUILD_ID, ORGANIZER_ID, "room".into())
+            .await
+            .unwrap();
     }
 
-    /// コマンドが実行されたときに呼ばれる
-    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        // 不明なインタラクションは無視
-        match interaction {
-            Interaction::ApplicationCommand(interaction) => {
-                match self.on_move_command(&ctx, &interaction).await {
-                    Ok(_) => {}
-                    Err(why) => {
-                        match interaction
-                            .create_interaction_response(&ctx, |response| {
-                                response
-                                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                                    .interaction_response_data(|message| {
-                                        message.ephemeral(true);
-                                        message.content(why.to_string());
-                                        message
-                                    })
-                            })
-                            .await
-                        {
-                            Ok(_) => {}
-                            Err(why) => {
-                                error!("エラーメッセージの送信に失敗: {:?}", why);
-                            }
-                        }
-                    }
-                }
-            }
-            _ => return,
-        };
+    /// 最大文字数を超える文字列だけを、末尾の「…」を含めて最大文字数に切り詰める
+    #[test]
+    fn truncate_with_ellipsis_fits_max_length() {
+        assert_eq!(truncate_with_ellipsis("あいう".into(), 3), "あいう");
+        assert_eq!(truncate_with_ellipsis("あいうえ".into(), 3), "あい…");
+
+        let content = "🤚".repeat(MESSAGE_CONTENT_MAX_LENGTH + 1);
+        let truncated = truncate_with_ellipsis(content, MESSAGE_CONTENT_MAX_LENGTH);
+        assert_eq!(truncated.chars().count(), MESSAGE_CONTENT_MAX_LENGTH);
+        assert!(truncated.ends_with('…'));
     }
 
-    /// リアクションを追加したときに呼ばれる
-    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
-        match self.on_move_reaction(&ctx, &reaction).await {
-            Ok(_) => {}
-            Err(why) => {
-                error!("リアクションの反応に失敗: {:?}", why);
-                return;
-            }
+    /// 結果の項目に収まらないメンバーは省略し、残りの人数を表示して最大文字数に収める
+    #[test]
+    fn mention_lines_fit_embed_field() {
+        let strings = strings::lookup("ja").unwrap();
+        let members = (0..3)
+            .map(|i| mock::member(GUILD_ID, UserId(100_000_000_000_000_000 + i), &[]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mention_lines(&members, strings.and_more),
+            members
+                .iter()
+                .map(|member| member.mention().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+
+        // 1行は21文字のメンションと改行で、100人分は項目の最大文字数を超える
+        let members = (0..100)
+            .map(|i| mock::member(GUILD_ID, UserId(100_000_000_000_000_000 + i), &[]))
+            .collect::<Vec<_>>();
+        let value = mention_lines(&members, strings.and_more);
+        assert!(value.chars().count() <= EMBED_FIELD_VALUE_MAX_LENGTH);
+        let lines = value.lines().collect::<Vec<_>>();
+        let shown = lines.len() - 1;
+        assert_eq!(shown, 46);
+        for (line, member) in lines[..shown].iter().zip(&members) {
+            assert_eq!(*line, member.mention().to_string());
+        }
+        assert_eq!(lines[shown], strings.and_more.format(&[100 - shown]));
+    }
+
+    /// 空になった移動元のVCは削除するが、ロビーのVCは空になっても残す
+    #[tokio::test(start_paused = true)]
+    async fn cleanup_empty_sources_keeps_lobby() {
+        let lobby_channel_id = ChannelId(70);
+        let mut app_config = test_config();
+        app_config.discord.lobby_channel = Some(lobby_channel_id);
+        let settings = app_config.guild(GUILD_ID).unwrap();
+        let (handler, discord) = test_handler(app_config, "cleanup_keeps_lobby");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[(ORGANIZER_ID, DESTINATION_CHANNEL_ID)],
+            &[],
+        );
+        for channel_id in [SOURCE_CHANNEL_ID, lobby_channel_id] {
+            discord.channels.lock().unwrap().insert(
+                channel_id,
+                mock::voice_channel(GUILD_ID, channel_id, Some(settings.vc_category), 0),
+            );
         }
+
+        let started = tokio::time::Instant::now();
+        handler
+            .cleanup_empty_sources(
+                &test_context(),
+                &settings,
+                GUILD_ID,
+                &[lobby_channel_id, SOURCE_CHANNEL_ID],
+            )
+            .await;
+
+        assert_eq!(
+            discord
+                .calls()
+                .into_iter()
+                .filter(|call| matches!(call, Call::DeleteChannel(_)))
+                .collect::<Vec<_>>(),
+            vec![Call::DeleteChannel(SOURCE_CHANNEL_ID)]
+        );
+        // 移動が反映されるまで待つのは一度だけ
+        assert_eq!(
+            started.elapsed(),
+            Duration::from_secs(settings.move_wait_seconds)
+        );
+    }
+
+    /// 投票の絵文字を1つ外しても、ほかの投票の絵文字でリアクションしていれば参加者から外さない
+    #[tokio::test(start_paused = true)]
+    async fn removing_one_of_vote_emojis_keeps_participant() {
+        let participant_id = UserId(101);
+        let mut app_config = test_config();
+        app_config.discord.vote_emoji = vec!["🤚".to_string(), "👍".to_string()];
+        let (handler, discord) = test_handler(app_config, "remove_one_vote_emoji");
+        mock_server(
+            &discord,
+            mock::voice_channel(GUILD_ID, DESTINATION_CHANNEL_ID, None, 0),
+            &[
+                (ORGANIZER_ID, SOURCE_CHANNEL_ID),
+                (participant_id, SOURCE_CHANNEL_ID),
+            ],
+            &[],
+        );
+        discord
+            .emoji_reaction_users
+            .lock()
+            .unwrap()
+            .insert("👍".to_string(), vec![mock::user(participant_id, false)]);
+        handler.votes.lock().await.insert(
+            VOTE_MESSAGE_ID,
+            test_vote(
+                CommandType::MoveHere(DESTINATION_CHANNEL_ID),
+                vec![participant_id],
+            ),
+        );
+        let remove_hand: Reaction = serde_json::from_value(serde_json::json!({
+            "channel_id": TEXT_CHANNEL_ID.0.to_string(),
+            "message_id": VOTE_MESSAGE_ID.0.to_string(),
+            "guild_id": GUILD_ID.0.to_string(),
+            "user_id": participant_id.0.to_string(),
+            "emoji": { "id": null, "name": "🤚" },
+        }))
+        .unwrap();
+        let ctx = test_context();
+
+        // 👍 でもリアクションしているため参加したまま
+        handler
+            .on_move_reaction_remove(&ctx, &remove_hand)
+            .await
+            .unwrap();
+        assert_eq!(
+            handler.votes.lock().await[&VOTE_MESSAGE_ID].reactors,
+            vec![participant_id]
+        );
+
+        // 👍 も外していれば参加者から外す
+        discord.emoji_reaction_users.lock().unwrap().clear();
+        handler
+            .on_move_reaction_remove(&ctx, &remove_hand)
+            .await
+            .unwrap();
+        assert!(handler.votes.lock().await[&VOTE_MESSAGE_ID]
+            .reactors
+            .is_empty());
+        assert!(discord
+            .calls()
+            .iter()
+            .any(|call| matches!(call, Call::EditMessage(message_id, _) if *message_id == VOTE_MESSAGE_ID)));
     }
 }
@@ -1,12 +1,20 @@
 mod app_config;
+mod database;
+mod discord_api;
 mod event_handler;
+mod metrics;
+mod move_history;
+mod strings;
+mod vote_store;
 
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, Context as _, Result};
 use app_config::AppConfig;
+use discord_api::SerenityApi;
 use event_handler::Handler;
-use std::env;
+use log::{error, warn};
+use std::{env, sync::Arc};
 
-use serenity::prelude::*;
+use serenity::{gateway::GatewayError, http::Http, prelude::*};
 
 /// メイン処理
 #[tokio::main]
@@ -20,24 +28,93 @@ async fn main() -> Result<()> {
     // 設定ファイルを読み込む
     let app_config = AppConfig::load_config(&basedir).context("設定ファイルの読み込みに失敗")?;
 
+    // 開発用サーバーIDを取得
+    let dev_guild_id = app_config.discord.dev_guild_id;
+    // 統計を公開するポートを取得
+    let metrics_port = app_config.discord.metrics_port;
+    // 受け取るイベントの種類を取得
+    let intents = app_config.gateway_intents();
+
     // イベント受信リスナーを構築
-    let handler = Handler::new(app_config).context("イベント受信リスナーの構築に失敗")?;
+    let handler = Handler::new(app_config, &basedir, Arc::new(SerenityApi))
+        .context("イベント受信リスナーの構築に失敗")?;
 
     // 環境変数のトークンを使用してDiscord APIを初期化
     let token = env::var("DISCORD_TOKEN").context("トークンが指定されていません")?;
-    let intents = GatewayIntents::non_privileged()
-        | GatewayIntents::MESSAGE_CONTENT
-        | GatewayIntents::GUILD_MEMBERS;
+
+    // --register-only の場合はコマンドを開発用サーバーに登録して終了
+    if env::args().any(|arg| arg == "--register-only") {
+        let guild_id =
+            dev_guild_id.context("--register-only を使うには dev_guild_id の設定が必要です")?;
+        let http = Http::new(&token);
+        let application_info = http
+            .get_current_application_info()
+            .await
+            .context("アプリケーション情報の取得に失敗")?;
+        http.set_application_id(application_info.id.0);
+        handler
+            .register_command(&http, Some(guild_id))
+            .await
+            .context("コマンドの登録に失敗")?;
+        return Ok(());
+    }
+    let vote_shutdown = handler.vote_shutdown();
+
+    // 統計のHTTPサーバーを起動 (ポートが設定されている場合のみ)
+    if let Some(port) = metrics_port {
+        let metrics = handler.metrics();
+        tokio::spawn(async move {
+            if let Err(why) = metrics::serve(metrics, port).await {
+                error!("統計のHTTPサーバーの起動に失敗: {:?}", why);
+            }
+        });
+    }
     let mut client = Client::builder(token, intents)
         .event_handler(handler)
         .await
         .context("Botの初期化に失敗")?;
 
+    // 終了のシグナルを受け取ったら進行中の投票を片付けて終了
+    let shard_manager = client.shard_manager.clone();
+    let http = client.cache_and_http.http.clone();
+    tokio::spawn(async move {
+        if let Err(why) = wait_for_shutdown_signal().await {
+            error!("終了のシグナルの待機に失敗: {:?}", why);
+            return;
+        }
+        warn!("終了のシグナルを受信しました");
+        vote_shutdown.shutdown(&http).await;
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
     // イベント受信を開始
-    client
-        .start()
-        .await
-        .context("Bot動作中にエラーが発生しました")?;
+    // (特権インテントが許可されていない場合は設定方法を案内する)
+    client.start().await.map_err(|why| match why {
+        SerenityError::Gateway(GatewayError::DisallowedGatewayIntents) => anyhow!(
+            "特権インテント ({:?}) が許可されていません。Developer Portalで有効にするか、message_content_intent / guild_members_intent を false にしてください",
+            intents - GatewayIntents::non_privileged(),
+        ),
+        why => anyhow::Error::new(why).context("Bot動作中にエラーが発生しました"),
+    })?;
 
     Ok(())
 }
+
+/// Ctrl-C または SIGTERM を受け取るまで待つ
+async fn wait_for_shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .context("SIGTERMの待機に失敗")?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.context("Ctrl-Cの待機に失敗")?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c()
+        .await
+        .context("Ctrl-Cの待機に失敗")?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::{
+    convert::Infallible,
+    fmt::Write as _,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::{Context as _, Result};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+
+/// Prometheusで収集する統計
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// 開始した募集の数
+    pub votes_started: AtomicU64,
+    /// 移動したメンバーの数
+    pub members_moved: AtomicU64,
+    /// 時間切れになった募集の数
+    pub votes_timed_out: AtomicU64,
+    /// 移動に失敗したメンバーの数
+    pub move_failures: AtomicU64,
+    /// 進行中の募集の数
+    pub active_votes: AtomicU64,
+}
+
+impl Metrics {
+    /// カウンターを増やす
+    pub fn add(counter: &AtomicU64, value: usize) {
+        counter.fetch_add(value as u64, Ordering::Relaxed);
+    }
+
+    /// Prometheusのテキスト形式に変換
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, kind, help, value) in [
+            (
+                "vcmove_votes_started_total",
+                "counter",
+                "Number of move votes started",
+                &self.votes_started,
+            ),
+            (
+                "vcmove_members_moved_total",
+                "counter",
+                "Number of members moved",
+                &self.members_moved,
+            ),
+            (
+                "vcmove_votes_timed_out_total",
+                "counter",
+                "Number of move votes that timed out",
+                &self.votes_timed_out,
+            ),
+            (
+                "vcmove_move_failures_total",
+                "counter",
+                "Number of members that could not be moved",
+                &self.move_failures,
+            ),
+            (
+                "vcmove_active_votes",
+                "gauge",
+                "Number of move votes currently open",
+                &self.active_votes,
+            ),
+        ] {
+            let _ = writeln!(text, "# HELP {name} {help}");
+            let _ = writeln!(text, "# TYPE {name} {kind}");
+            let _ = writeln!(text, "{name} {}", value.load(Ordering::Relaxed));
+        }
+        text
+    }
+}
+
+/// 統計を公開するHTTPサーバーを起動 (GET /metrics で取得できる)
+pub async fn serve(metrics: Arc<Metrics>, port: u16) -> Result<()> {
+    let make_service = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let metrics = metrics.clone();
+                async move {
+                    match (request.method(), request.uri().path()) {
+                        (&Method::GET, "/metrics") => Response::builder()
+                            .header("Content-Type", "text/plain; version=0.0.4")
+                            .body(Body::from(metrics.render())),
+                        _ => Response::builder()
+                            .status(StatusCode::NOT_FOUND)
+                            .body(Body::empty()),
+                    }
+                }
+            }))
+        }
+    });
+
+    let address = SocketAddr::from(([0, 0, 0, 0], port));
+    Server::try_bind(&address)
+        .with_context(|| format!("統計のポート {} を開けません", port))?
+        .serve(make_service)
+        .await
+        .context("統計のHTTPサーバーでエラーが発生しました")
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use chrono::Local;
+use rusqlite::params;
+use serenity::model::prelude::{GuildId, UserId};
+
+use crate::database::Database;
+
+/// 完了した移動の記録 (集計して /move_stats で表示する)
+#[derive(Debug, Clone)]
+pub struct MoveHistory {
+    /// 保存先のデータベース
+    database: Database,
+}
+
+/// 期間内の移動の集計
+#[derive(Debug, Clone, Default)]
+pub struct MoveTotals {
+    /// 移動した回数
+    pub moves: u64,
+    /// 移動した人数の合計
+    pub members: u64,
+}
+
+impl MoveHistory {
+    /// コンストラクタ (記録するテーブルがなければ作成)
+    pub fn new(database: Database) -> Result<Self> {
+        database.with_connection(|connection| {
+            connection.execute_batch(
+                "CREATE TABLE IF NOT EXISTS move_history (
+                    id INTEGER PRIMARY KEY,
+                    guild_id TEXT NOT NULL,
+                    organizer_id TEXT NOT NULL,
+                    moved INTEGER NOT NULL,
+                    created_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS move_history_guild_created_at
+                    ON move_history (guild_id, created_at);",
+            )
+        })?;
+        Ok(Self { database })
+    }
+
+    /// 完了した移動を記録
+    pub fn record(&self, guild_id: GuildId, organizer_id: UserId, moved: usize) -> Result<()> {
+        self.database.with_connection(|connection| {
+            connection.execute(
+                "INSERT INTO move_history (guild_id, organizer_id, moved, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    guild_id.0.to_string(),
+                    organizer_id.0.to_string(),
+                    moved as i64,
+                    Local::now().timestamp(),
+                ],
+            )
+        })?;
+        Ok(())
+    }
+
+    /// 指定した日数以内の移動を集計
+    pub fn totals(&self, guild_id: GuildId, days: i64) -> Result<MoveTotals> {
+        self.database.with_connection(|connection| {
+            connection.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(moved), 0) FROM move_history WHERE guild_id = ?1 AND created_at >= ?2",
+                params![guild_id.0.to_string(), since(days)],
+                |row| {
+                    Ok(MoveTotals {
+                        moves: row.get::<_, i64>(0)? as u64,
+                        members: row.get::<_, i64>(1)? as u64,
+                    })
+                },
+            )
+        })
+    }
+
+    /// 指定した日数以内に移動した回数が多い主催者 (主催者, 回数)
+    pub fn top_organizers(
+        &self,
+        guild_id: GuildId,
+        days: i64,
+        limit: usize,
+    ) -> Result<Vec<(UserId, u64)>> {
+        self.database.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT organizer_id, COUNT(*) AS moves FROM move_history
+                    WHERE guild_id = ?1 AND created_at >= ?2
+                    GROUP BY organizer_id ORDER BY moves DESC, MAX(created_at) DESC LIMIT ?3",
+            )?;
+            let rows = statement
+                .query_map(
+                    params![guild_id.0.to_string(), since(days), limit as i64],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+                )?
+                .collect::<rusqlite::Result<Vec<(String, i64)>>>()?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|(organizer_id, moves)| {
+                    Some((UserId(organizer_id.parse().ok()?), moves as u64))
+                })
+                .collect())
+        })
+    }
+}
+
+/// 指定した日数前の時刻 (UNIX時間)
+fn since(days: i64) -> i64 {
+    Local::now().timestamp() - days * 24 * 60 * 60
+}
@@ -0,0 +1,665 @@
+/// ユーザーに表示する文字列
+///
+/// `{}` は順に置き換えられる
+pub struct Strings {
+    /// 言語コード
+    pub locale: &'static str,
+
+    /// moveコマンドの説明
+    pub move_description: &'static str,
+    /// move_toコマンドの説明
+    pub move_to_description: &'static str,
+    /// move_hereコマンドの説明
+    pub move_here_description: &'static str,
+    /// move_randomコマンドの説明
+    pub move_random_description: &'static str,
+    /// move_usersコマンドの説明
+    pub move_users_description: &'static str,
+    /// dismissコマンドの説明
+    pub dismiss_description: &'static str,
+    /// move_selftestコマンドの説明
+    pub move_selftest_description: &'static str,
+    /// move_retargetコマンドの説明
+    pub move_retarget_description: &'static str,
+    /// cancel_moveコマンドの説明
+    pub cancel_move_description: &'static str,
+    /// list_votesコマンドの説明
+    pub list_votes_description: &'static str,
+    /// reload_configコマンドの説明
+    pub reload_config_description: &'static str,
+    /// move_statsコマンドの説明
+    pub move_stats_description: &'static str,
+    /// set_enabledコマンドの説明
+    pub set_enabled_description: &'static str,
+    /// cleanup_channelsコマンドの説明
+    pub cleanup_channels_description: &'static str,
+    /// channel_nameオプションの説明
+    pub option_channel_name: &'static str,
+    /// messageオプションの説明
+    pub option_message: &'static str,
+    /// reasonオプションの説明
+    pub option_reason: &'static str,
+    /// limit_toオプションの説明
+    pub option_limit_to: &'static str,
+    /// return_after_minutesオプションの説明
+    pub option_return_after_minutes: &'static str,
+    /// follow_duration_secondsオプションの説明
+    pub option_follow_duration_seconds: &'static str,
+    /// source_channelsオプションの説明
+    pub option_source_channels: &'static str,
+    /// channelオプションの説明
+    pub option_channel: &'static str,
+    /// usersオプションの説明
+    pub option_users: &'static str,
+    /// move_retargetのchannelオプションの説明
+    pub option_new_channel: &'static str,
+    /// set_enabledのenabledオプションの説明
+    pub option_enabled: &'static str,
+    /// cleanup_channelsのconfirmオプションの説明
+    pub option_confirm: &'static str,
+    /// move_randomのcountオプションの説明
+    pub option_count: &'static str,
+
+    /// 募集メッセージ (主催者, 移動先, 単位付きの制限時間。{emoji} は投票の絵文字に置き換える)
+    pub vote_prompt: &'static str,
+    /// 以前の募集メッセージ (更新前に投稿した募集も読み取れるようにする)
+    pub legacy_vote_prompts: &'static [&'static str],
+    /// 分単位の時間の表記 (分)
+    pub duration_minutes: &'static str,
+    /// 秒単位の時間の表記 (秒)
+    pub duration_seconds: &'static str,
+    /// 新規作成するVCの表記 (チャンネル名)
+    pub new_channel: &'static str,
+    /// ランダムに分ける新規VCの表記 (VCの数)
+    pub random_rooms: &'static str,
+    /// ランダムに分けるときに作成するVCの名前 (番号)
+    pub random_room_name: &'static str,
+    /// 募集メッセージの前置き (移動元のVC, VCにいるメンバー)
+    pub vote_header: &'static str,
+    /// 募集メッセージの理由 (理由)
+    pub reason_line: &'static str,
+    /// 募集メッセージの移動できるメンバーの行 (ユーザーまたはロール)
+    pub limit_to_line: &'static str,
+    /// 移動後もあとから参加した人を移動する時間 ({}には時間が入る)
+    pub follow_line: &'static str,
+    /// 参加ボタンのラベル (人数)
+    pub vote_button: &'static str,
+    /// リアクションで参加する方法 (絵文字)
+    pub how_to_join_reaction: &'static str,
+    /// ボタンで参加する方法 (絵文字)
+    pub how_to_join_button: &'static str,
+    /// 募集を開始したときの返信 (参加する方法, 移動先)
+    pub vote_started: &'static str,
+    /// 募集をスレッドに投稿したときに返信に付ける行 (スレッド)
+    pub vote_thread_line: &'static str,
+    /// 募集を投稿するスレッドの名前 (主催者の名前)
+    pub vote_thread_name: &'static str,
+    /// 募集メッセージに表示する参加予定の人数 (主催者を含む人数)
+    pub participants_count: &'static str,
+    /// 完了した募集メッセージに表示する文字列 (移動先)
+    pub vote_completed: &'static str,
+    /// 移動後もあとから参加した人を移動している募集メッセージに表示する文字列 ({}には移動先と残りの時間が入る)
+    pub vote_following: &'static str,
+    /// Botの終了により、あとから参加した人の移動をやめた募集メッセージに表示する文字列
+    pub follow_cancelled: &'static str,
+    /// まもなく締め切る募集メッセージに表示する文字列
+    pub closing_soon: &'static str,
+    /// 参加したときの返信 (主催者)
+    pub joined: &'static str,
+    /// 参加を取り消したときの返信
+    pub left: &'static str,
+    /// 移動後に参加して移動先へ移動したときの返信 ({}には移動先が入る)
+    pub follow_moved: &'static str,
+    /// 移動後に参加した人が移動できるメンバーに含まれていないときのエラー
+    pub follow_not_allowed: &'static str,
+    /// 移動対象外のメンバーが移動後に参加したときの返信
+    pub follow_exempt: &'static str,
+    /// 募集が終了していたときのエラー
+    pub vote_closed: &'static str,
+    /// 募集開始から最低限の時間が経っていないときの案内 (残り秒数)
+    pub wait_more: &'static str,
+    /// 参加者が足りないときの案内 (足りない人数)
+    pub not_enough_participants: &'static str,
+    /// 参加者が集まらないまま時間切れになったときの通知 (最低人数)
+    pub vote_not_established: &'static str,
+    /// 誰も参加しないまま時間切れになったときの通知
+    pub vote_expired: &'static str,
+    /// すでに募集中のときのエラー (募集メッセージのリンク)
+    pub already_voting: &'static str,
+    /// 同じチャンネルで募集中のときのエラー (募集メッセージのリンク)
+    pub already_voting_in_channel: &'static str,
+    /// 同じチャンネルやVCで募集を開始しているところのときのエラー
+    pub vote_starting: &'static str,
+    /// 移動先を変更したときの返信 (移動先)
+    pub retargeted: &'static str,
+    /// 募集を取り消したときの返信
+    pub cancelled: &'static str,
+    /// 進行中の投票の一覧のタイトル
+    pub list_votes_title: &'static str,
+    /// 進行中の投票の一覧の項目 (募集メッセージのリンク, 主催者, 移動先, 参加人数, 単位付きの残り時間)
+    pub list_votes_entry: &'static str,
+    /// 進行中の投票がないときの返信
+    pub no_active_votes: &'static str,
+    /// 移動の統計のタイトル
+    pub move_stats_title: &'static str,
+    /// 移動の統計の期間 (日数)
+    pub move_stats_period: &'static str,
+    /// 移動の統計の期間ごとの集計 (移動した回数, 移動した人数)
+    pub move_stats_totals: &'static str,
+    /// 移動の統計のよく募集する人の見出し (日数)
+    pub move_stats_top_organizers: &'static str,
+    /// 移動の統計のよく募集する人の項目 (順位, 主催者, 移動した回数)
+    pub move_stats_organizer_entry: &'static str,
+    /// 移動の記録がないときの表示
+    pub move_stats_empty: &'static str,
+    /// 設定を再読み込みしたときの返信のタイトル
+    pub config_reloaded: &'static str,
+    /// 再読み込みした設定に変更がなかったときの返信
+    pub config_unchanged: &'static str,
+    /// 新しい募集の受け付けを停止しているときのエラー
+    pub maintenance: &'static str,
+    /// 新しい募集の受け付けを再開したときの返信
+    pub votes_enabled: &'static str,
+    /// 新しい募集の受け付けを停止したときの返信
+    pub votes_disabled: &'static str,
+    /// cleanup_channelsで確認せずに実行したときの返信 ({}には削除するVCの数と一覧が入る)
+    pub cleanup_channels_preview: &'static str,
+    /// cleanup_channelsで削除したときの返信 ({}には削除したVCの数と失敗したVCの数が入る)
+    pub cleanup_channels_done: &'static str,
+    /// 再起動するまで反映されない設定に付ける注意
+    pub config_restart_required: &'static str,
+    /// 設定の再読み込みに失敗したときのエラー (エラーの内容)
+    pub config_reload_failed: &'static str,
+
+    /// コマンドを使う権限がないときのエラー
+    pub no_permission: &'static str,
+    /// 募集を開始してからクールダウン中のときのエラー (残り秒数)
+    pub cooldown: &'static str,
+    /// メンバーを移動する権限がないときのエラー
+    pub no_move_permission: &'static str,
+    /// Botのオーナー以外が使ったときのエラー
+    pub owner_only: &'static str,
+    /// 動作確認用のカテゴリが設定されていないときのエラー
+    pub selftest_category_not_configured: &'static str,
+    /// 動作確認の結果 (各手順の結果)
+    pub selftest_result: &'static str,
+    /// 動作確認のVCの作成の手順 (VCの名前)
+    pub selftest_create_channel: &'static str,
+    /// 動作確認のVCの名前の変更の手順
+    pub selftest_rename_channel: &'static str,
+    /// 動作確認のメンバーの移動権限の手順 (VCの名前)
+    pub selftest_move_permission: &'static str,
+    /// 動作確認のVCの削除の手順 (VCの名前)
+    pub selftest_delete_channel: &'static str,
+    /// チャンネルが指定されていないときのエラー
+    pub channel_not_specified: &'static str,
+    /// チャンネルが取得できないときのエラー
+    pub channel_not_found: &'static str,
+    /// ユーザーが指定されていないときのエラー
+    pub users_not_specified: &'static str,
+    /// ユーザーがメンションで指定されていないときのエラー
+    pub users_mention_required: &'static str,
+    /// サーバーが見つからないときのエラー
+    pub guild_not_found: &'static str,
+    /// サーバーの取得に失敗したときのエラー
+    pub guild_fetch_failed: &'static str,
+    /// コマンドを送信したユーザーを取得できないときのエラー
+    pub sender_not_found: &'static str,
+    /// コマンドへの応答に失敗したときのエラー
+    pub response_failed: &'static str,
+    /// set_enabledコマンドでenabledが指定されていないときのエラー
+    pub enabled_not_specified: &'static str,
+    /// サーバーの設定がないときのエラー
+    pub guild_not_configured: &'static str,
+    /// サーバーの設定に誤りがあるときのエラー
+    pub guild_misconfigured: &'static str,
+    /// ボイスチャンネルに参加していないときのエラー
+    pub not_in_voice: &'static str,
+    /// move_hereで主催者がボイスチャンネルに参加していないときのエラー
+    pub move_here_not_in_voice: &'static str,
+    /// VC作成チャンネルへ移動してもVCが作成されなかったときのエラー
+    pub channel_not_created: &'static str,
+    /// BotにVCを作成する権限がないときのエラー
+    pub create_channel_no_permission: &'static str,
+    /// VCを作成するカテゴリのチャンネル数が上限に達しているときのエラー
+    pub category_full: &'static str,
+    /// Botが作成したVCの数がサーバーごとの上限に達しているときのエラー
+    pub created_channel_limit: &'static str,
+    /// 移動先のVCに入る権限がないときのエラー
+    pub no_connect_permission: &'static str,
+    /// 移動先に指定できない種類のチャンネルのときのエラー
+    pub not_voice_channel: &'static str,
+    /// move_toの移動先に指定できないカテゴリのチャンネルのときのエラー ({}には指定できるカテゴリが入る)
+    pub category_not_allowed: &'static str,
+    /// 除外対象のチャンネルのときのエラー
+    pub ignored_channel: &'static str,
+    /// 一緒に集めるVCにボイスチャンネル以外を指定したときのエラー
+    pub source_channel_not_voice: &'static str,
+    /// dismissで移動先のロビーが設定されていないときのエラー
+    pub lobby_not_configured: &'static str,
+    /// dismissで主催者がすでにロビーにいるときのエラー
+    pub already_in_lobby: &'static str,
+    /// Botが募集を投稿するチャンネルで必要な権限を持っていないときのエラー ({}には足りない権限が入る)
+    pub bot_missing_permissions: &'static str,
+    /// 設定したカテゴリの外のチャンネルのときのエラー
+    pub wrong_category: &'static str,
+    /// チャンネル名が入力されていないときのエラー
+    pub channel_name_required: &'static str,
+    /// チャンネル名が許可されたパターンに一致しないときのエラー
+    pub channel_name_not_allowed: &'static str,
+    /// チャンネル名に禁止された単語が含まれているときのエラー
+    pub channel_name_blocked: &'static str,
+    /// チャンネル名が長すぎるときのエラー (最大文字数)
+    pub channel_name_too_long: &'static str,
+    /// チャンネル名に使用できない文字が含まれているときのエラー
+    pub channel_name_invalid_chars: &'static str,
+    /// デフォルトのチャンネル名がDiscordの制限を満たさないときのエラー
+    pub default_channel_name_invalid: &'static str,
+    /// 新しい移動先が指定されていないときのエラー
+    pub retarget_target_required: &'static str,
+    /// 進行中の募集がないときのエラー
+    pub no_active_vote: &'static str,
+    /// 他の人が開始した募集を取り消そうとしたときのエラー
+    pub cancel_not_organizer: &'static str,
+    /// 募集メッセージの投稿に失敗したときのエラー
+    pub post_failed: &'static str,
+    /// 募集メッセージにリアクションを付与できなかったときのエラー
+    pub vote_reaction_failed: &'static str,
+
+    /// move_usersの結果 (移動先, ユーザーごとの結果)
+    pub move_users_result: &'static str,
+    /// move_usersで移動できたユーザーの結果
+    pub move_users_moved: &'static str,
+    /// move_usersで移動できなかったユーザーの結果
+    pub move_users_failed: &'static str,
+    /// 主催者と一緒に移動したメンバー (主催者, 人数)
+    pub moved_with: &'static str,
+    /// 主催者の呼びかけで移動したメンバー (主催者, 人数)
+    pub moved_by: &'static str,
+    /// 移動先VCのチャットへの通知 (移動したメンバー)
+    pub arrived: &'static str,
+    /// 移動結果 (移動したメンバー, 移動先)
+    pub moved_result: &'static str,
+    /// 移動結果の埋め込みのタイトル
+    pub moved_members_title: &'static str,
+    /// ランダムに分けた移動結果の埋め込みのタイトル
+    pub random_groups_title: &'static str,
+    /// 移動結果の埋め込みの理由の項目名
+    pub reason_field: &'static str,
+    /// 移動結果の埋め込みの移動できなかったメンバーの項目名
+    pub move_failed_field: &'static str,
+    /// 移動結果の埋め込みのVCに入る権限がないメンバーの項目名
+    pub no_connect_permission_field: &'static str,
+    /// 結果のメッセージの移動できるメンバーに含まれないため移動しなかったメンバーの見出し
+    pub limit_to_ignored_field: &'static str,
+    /// 移動結果の埋め込みの移動対象外のメンバーの項目名
+    pub move_exempt_field: &'static str,
+    /// 移動結果の埋め込みの移動先のVCが満員のため移動しなかったメンバーの項目名
+    pub destination_full_field: &'static str,
+    /// 移動結果の埋め込みの項目に収まらずに省略したメンバーの人数
+    pub and_more: &'static str,
+    /// 主催者へのDM (サーバー, 移動先, 移動した人数, 移動できなかったメンバー)
+    pub dm_summary: &'static str,
+    /// 移動したメンバーへのDM (サーバー, 移動先)
+    pub moved_dm: &'static str,
+    /// DMを送信できなかった人数 (人数)
+    pub moved_dm_failed: &'static str,
+    /// 該当なし
+    pub none: &'static str,
+    /// 元のVCへ戻る予告 (元のVC)
+    pub return_soon: &'static str,
+    /// 元のVCへ戻した結果 (人数, 移動先, 元のVC)
+    pub returned: &'static str,
+    /// Webhookの名前
+    pub webhook_name: &'static str,
+}
+
+/// 日本語
+const JA: Strings = Strings {
+    locale: "ja",
+
+    move_description: "みんなでVCを移動する投票ボタンを作成します",
+    move_to_description: "みんなでVCを移動する投票ボタンを作成します",
+    move_here_description: "自分が参加しているVCへみんなを呼び寄せる投票ボタンを作成します",
+    move_random_description: "参加した人をランダムに複数の新しいVCへ分けて移動する投票ボタンを作成します",
+    move_users_description: "指定したユーザーを投票なしでVCへ移動します (メンバーを移動権限が必要)",
+    dismiss_description: "自分が参加しているVCの全員をロビーのVCへ移動します",
+    move_selftest_description: "Botの権限とAPIの動作を確認します (Botのオーナーのみ)",
+    move_retarget_description: "進行中の募集の移動先を変更します (募集を開始した人のみ)",
+    cancel_move_description: "進行中の募集を取り消します (募集を開始した人のみ)",
+    list_votes_description: "このサーバーで進行中の投票を一覧表示します",
+    reload_config_description: "設定ファイルを再読み込みします (Botのオーナーのみ)",
+    move_stats_description: "このサーバーの移動の統計を表示します",
+    set_enabled_description: "新しい募集の受け付けを停止・再開します (Botのオーナーのみ)",
+    cleanup_channels_description: "カテゴリの中の誰もいないVCを削除します (Botのオーナーのみ)",
+    option_channel_name: "新規作成するチャンネル名",
+    option_message: "募集メッセージ",
+    option_reason: "移動の理由",
+    option_limit_to: "移動できるメンバーを指定したユーザーまたはロールのメンバーに限定します",
+    option_return_after_minutes: "移動後、指定した分数が経ったら元のVCへ戻します",
+    option_follow_duration_seconds:
+        "移動後も指定した秒数の間、あとから参加した人を移動先へ移動します",
+    option_source_channels: "一緒に集めるVC (チャンネルのメンションまたはIDを空白区切りで複数指定)",
+    option_channel: "移動先のチャンネル",
+    option_users: "移動するユーザーのメンション (複数可)",
+    option_new_channel: "新しい移動先のチャンネル",
+    option_enabled: "新しい募集を受け付けるか",
+    option_confirm: "Trueにすると実際に削除します (Falseの場合は削除するVCを表示するだけ)",
+    option_count: "分けるVCの数",
+
+    vote_prompt:
+        "{}が一緒に移動する人の募集を開始しました。\n{}に移動したい人は{}以内に{emoji}を押してください！",
+    legacy_vote_prompts: &[
+        "{}が一緒に移動する人の募集を開始しました。\n{}に移動したい人は{}分以内にリアクション押してください！",
+    ],
+    duration_minutes: "{}分",
+    duration_seconds: "{}秒",
+    new_channel: "新規VC「{}」",
+    random_rooms: "ランダムに分けた{}個の新規VC",
+    random_room_name: "グループ{}",
+    vote_header: "{}にいる皆さん({})へ",
+    reason_line: "\n理由: {}",
+    limit_to_line: "\n移動できるメンバー: {}",
+    follow_line: "\n移動後も{}の間、あとから参加した人を移動します",
+    vote_button: "一緒に移動する ({}人)",
+    how_to_join_reaction: "{}をつける",
+    how_to_join_button: "{}のボタンを押す",
+    vote_started:
+        "一緒に移動する人の募集を開始しました。\nあなたが{}と、参加した人と一緒に{}へ移動します。",
+    vote_thread_line: "\n募集はスレッド {} に投稿しました。",
+    vote_thread_name: "{}さんの移動の募集",
+    participants_count: "現在 {} 人が参加予定",
+    closing_soon: "⏰ まもなく募集を締め切ります",
+    vote_completed: "✅ 募集を締め切り、{}へ移動しました",
+    vote_following: "🔁 {}へ移動しました。あと{}の間、参加した人も移動します",
+    follow_cancelled: "Botが終了したため、あとから参加した人の移動を終了しました。",
+    joined: "参加しました。{}が移動するときに一緒に移動します。(もう一度押すと参加を取り消します)",
+    left: "参加を取り消しました。",
+    follow_moved: "{}へ移動しました。",
+    follow_not_allowed: "移動できるメンバーに含まれていないため移動しません",
+    follow_exempt: "移動対象外に設定されているため移動しません",
+    vote_closed: "この募集はすでに終了しています",
+    wait_more: "もう少し待ってください (あと{}秒で移動できます)",
+    not_enough_participants: "参加者が足りません (あと{}人の参加が必要です)",
+    vote_not_established: "募集は成立しませんでした ({}人以上の参加が必要です)",
+    vote_expired: "時間切れで移動は行われませんでした",
+    already_voting: "このVCではすでに募集中です\n{}",
+    already_voting_in_channel:
+        "このチャンネルではすでに募集中です。募集が終わってから開始してください\n{}",
+    vote_starting: "同じチャンネルかVCで募集を開始しているところです。少し待ってからもう一度お試しください",
+    retargeted: "移動先を{}に変更しました。",
+    cancelled: "募集を取り消しました。",
+    list_votes_title: "進行中の投票",
+    list_votes_entry: "{}\n{} → {} (参加 {}人, 残り{})",
+    no_active_votes: "現在進行中の投票はありません",
+    move_stats_title: "移動の統計",
+    move_stats_period: "過去{}日間",
+    move_stats_totals: "{}回 / {}人",
+    move_stats_top_organizers: "よく募集する人 (過去{}日間)",
+    move_stats_organizer_entry: "{}. {} ({}回)",
+    move_stats_empty: "まだ移動の記録はありません",
+    config_reloaded: "設定を再読み込みしました",
+    config_unchanged: "設定を再読み込みしました (変更された設定はありません)",
+    maintenance: "現在メンテナンス中です",
+    votes_enabled: "新しい募集の受け付けを再開しました",
+    votes_disabled: "新しい募集の受け付けを停止しました (進行中の募集はそのまま締め切れます)",
+    cleanup_channels_preview: "誰もいないVCが{}個あります。削除するには confirm を True にして実行してください\n{}",
+    cleanup_channels_done: "誰もいないVCを{}個削除しました (失敗: {}個)",
+    config_restart_required: " (再起動後に反映されます)",
+    config_reload_failed: "設定を再読み込みできませんでした (設定は変更していません)\n{}",
+
+    no_permission: "このコマンドを使う権限がありません",
+    cooldown: "クールダウン中です。あと{}秒お待ちください",
+    no_move_permission: "メンバーを移動する権限がありません",
+    owner_only: "このコマンドはBotのオーナーのみ使用できます",
+    selftest_category_not_configured: "selftest_category が設定されていません",
+    selftest_result: "動作確認の結果\n{}",
+    selftest_create_channel: "VCの作成 ({})",
+    selftest_rename_channel: "VCの名前の変更",
+    selftest_move_permission: "メンバーの移動権限 ({})",
+    selftest_delete_channel: "VCの削除 ({})",
+    channel_not_specified: "チャンネルが指定されていません",
+    channel_not_found: "チャンネルが取得できません",
+    users_not_specified: "ユーザーが指定されていません",
+    users_mention_required: "移動するユーザーをメンションで指定してください",
+    guild_not_found: "サーバーが見つかりません",
+    guild_fetch_failed: "サーバーの取得に失敗しました",
+    sender_not_found: "送信したユーザーを取得できませんでした",
+    response_failed: "コマンドの応答に失敗しました",
+    enabled_not_specified: "enabledオプションが指定されていません",
+    guild_not_configured:
+        "このサーバーではまだBotの設定が行われていません。管理者に設定を依頼してください",
+    guild_misconfigured:
+        "このサーバーのBotの設定に誤りがあります。管理者に設定の確認を依頼してください",
+    not_in_voice: "ボイスチャンネルに参加していません",
+    move_here_not_in_voice: "移動先にするボイスチャンネルに参加してから実行してください",
+    channel_not_created: "VCが作成されませんでした。しばらくしてからもう一度お試しください",
+    create_channel_no_permission: "BotにVCを作成する権限 (チャンネルの管理) がないため、VCを作成できません。管理者に権限の設定を依頼してください",
+    category_full: "カテゴリがいっぱいです。VCを作成できないため、使っていないVCを削除してからもう一度お試しください",
+    created_channel_limit: "このサーバーでBotが作成したVCが上限の{}個に達しています。使い終わったVCが削除されてからもう一度お試しください",
+    no_connect_permission: "指定されたVCに入る権限がありません",
+    not_voice_channel: "移動先にはボイスチャンネルを指定してください",
+    category_not_allowed: "移動先に指定できるのは次のカテゴリのVCのみです: {}",
+    ignored_channel: "除外対象のチャンネルです",
+    source_channel_not_voice: "一緒に集めるVCにはこのサーバーのボイスチャンネルを指定してください",
+    lobby_not_configured: "移動先のロビーのVCが設定されていません。管理者に設定を依頼してください",
+    already_in_lobby: "すでにロビーのVCにいます",
+    bot_missing_permissions: "Botにこのチャンネルで必要な権限がありません: {}",
+    wrong_category: "カテゴリが違います",
+    channel_name_required: "チャンネル名を入力してください",
+    channel_name_not_allowed: "このチャンネル名は使用できません",
+    channel_name_blocked: "チャンネル名に使用できない単語が含まれています",
+    channel_name_too_long: "チャンネル名は{}文字以内で入力してください",
+    channel_name_invalid_chars: "チャンネル名に改行などの使用できない文字が含まれています",
+    default_channel_name_invalid:
+        "デフォルトのチャンネル名はDiscordの制限(1〜100文字)を満たしていません",
+    retarget_target_required: "新しい移動先のチャンネルかチャンネル名を指定してください",
+    no_active_vote: "あなたが開始した進行中の募集がありません",
+    cancel_not_organizer: "募集を取り消せるのは募集を開始した人のみです",
+    post_failed: "メッセージの投稿に失敗しました",
+    vote_reaction_failed: "混雑のため募集メッセージにリアクションを付けられませんでした。少し待ってからもう一度お試しください",
+
+    move_users_result: "{}へのメンバーの移動結果\n{}",
+    move_users_moved: "✅ 移動しました",
+    move_users_failed: "❌ 移動に失敗しました",
+    moved_with: "{}と一緒に{}人のメンバー",
+    moved_by: "{}の呼びかけで{}人のメンバー",
+    arrived: "{}が移動してきました！",
+    moved_result: "{}を{}へ移動しました。",
+    moved_members_title: "移動したメンバー",
+    random_groups_title: "グループ分け",
+    reason_field: "理由",
+    move_failed_field: "移動できなかったメンバー",
+    no_connect_permission_field: "VCに入る権限がないため移動しなかったメンバー",
+    limit_to_ignored_field: "移動できるメンバーに含まれないため移動しなかったメンバー",
+    move_exempt_field: "移動対象外",
+    destination_full_field: "移動先のVCが満員のため移動しなかったメンバー",
+    and_more: "…ほか{}人",
+    dm_summary:
+        "{}のメンバーを{}へ移動しました。\n移動したメンバー: {}人\n移動できなかったメンバー: {}",
+    moved_dm: "{}の{}へ移動しました。",
+    moved_dm_failed: "{}人にDMを送信できませんでした",
+    none: "なし",
+    return_soon: "まもなく{}へ戻ります。",
+    returned: "{}人のメンバーを{}から{}へ戻しました。",
+    webhook_name: "VC移動",
+};
+
+/// 英語
+const EN: Strings = Strings {
+    locale: "en",
+
+    move_description: "Create a vote to move to a new VC together",
+    move_to_description: "Create a vote to move to an existing VC together",
+    move_here_description: "Create a vote to bring everyone to the VC you are in",
+    move_random_description: "Create a vote to split everyone who joins into several new VCs at random",
+    move_users_description:
+        "Move the specified users to a VC without a vote (requires the Move Members permission)",
+    dismiss_description: "Move everyone in your VC to the lobby VC",
+    move_selftest_description: "Check the bot's permissions and API access (bot owner only)",
+    move_retarget_description: "Change the destination of your active vote (organizer only)",
+    cancel_move_description: "Cancel your active vote (organizer only)",
+    list_votes_description: "List the active votes in this server",
+    reload_config_description: "Reload the config file (bot owner only)",
+    move_stats_description: "Show move statistics for this server",
+    set_enabled_description: "Stop or resume accepting new votes (bot owner only)",
+    cleanup_channels_description: "Delete empty VCs in the category (bot owner only)",
+    option_channel_name: "Name of the VC to create",
+    option_message: "Message for the vote",
+    option_reason: "Reason for the move",
+    option_limit_to: "Only move members who are this user or have this role",
+    option_return_after_minutes: "Return to the original VC after this many minutes",
+    option_follow_duration_seconds: "Keep moving people who join late for this many seconds after the move",
+    option_source_channels: "Other VCs to gather people from (channel mentions or IDs separated by spaces)",
+    option_channel: "Destination channel",
+    option_users: "Mentions of the users to move (multiple allowed)",
+    option_new_channel: "New destination channel",
+    option_enabled: "Whether to accept new votes",
+    option_confirm: "Set to True to actually delete (False only lists the VCs to delete)",
+    option_count: "Number of VCs to split into",
+
+    vote_prompt: "{} is gathering people to move together.\nIf you want to move to {}, press {emoji} within {}!",
+    legacy_vote_prompts: &[],
+    duration_minutes: "{} minutes",
+    duration_seconds: "{} seconds",
+    new_channel: "new VC \"{}\"",
+    random_rooms: "{} new VCs at random",
+    random_room_name: "Group {}",
+    vote_header: "To everyone in {} ({}): ",
+    reason_line: "\nReason: {}",
+    limit_to_line: "\nOnly for: {}",
+    follow_line: "\nPeople who join within {} after the move will be moved too",
+    vote_button: "Move together ({})",
+    how_to_join_reaction: "react with {}",
+    how_to_join_button: "press the {} button",
+    vote_started: "Started gathering people to move together.\nWhen you {}, you will move to {} together with everyone who joined.",
+    vote_thread_line: "\nThe vote was posted in the thread {}.",
+    vote_thread_name: "{}'s move vote",
+    participants_count: "{} people are planning to join",
+    closing_soon: "⏰ This vote is closing soon",
+    vote_completed: "✅ This vote is closed. Moved to {}",
+    vote_following: "🔁 Moved to {}. Anyone who joins in the next {} will be moved too",
+    follow_cancelled: "Stopped moving late joiners because the bot shut down.",
+    joined: "You joined. You will move when {} starts the move. (Press again to leave)",
+    left: "You left the move.",
+    follow_moved: "Moved you to {}.",
+    follow_not_allowed: "You are not among the members allowed to move",
+    follow_exempt: "You are exempt from being moved",
+    vote_closed: "This vote has already ended",
+    wait_more: "Please wait a little longer (you can move in {} seconds)",
+    not_enough_participants: "Not enough participants ({} more needed)",
+    vote_not_established: "The vote did not pass (at least {} participants are needed)",
+    vote_expired: "Time is up, so no one was moved",
+    already_voting: "A vote is already running for this VC\n{}",
+    already_voting_in_channel: "A vote is already running in this channel. Start a new one after it ends\n{}",
+    vote_starting: "A vote is being started in the same channel or VC. Please try again in a moment",
+    retargeted: "Changed the destination to {}.",
+    cancelled: "Cancelled the vote.",
+    list_votes_title: "Active votes",
+    list_votes_entry: "{}\n{} → {} ({} joined, {} left)",
+    no_active_votes: "There are no active votes",
+    move_stats_title: "Move statistics",
+    move_stats_period: "Last {} days",
+    move_stats_totals: "{} moves / {} members",
+    move_stats_top_organizers: "Top organizers (last {} days)",
+    move_stats_organizer_entry: "{}. {} ({} moves)",
+    move_stats_empty: "No moves recorded yet",
+    config_reloaded: "Reloaded the config",
+    config_unchanged: "Reloaded the config (nothing changed)",
+    maintenance: "The bot is currently under maintenance",
+    votes_enabled: "Resumed accepting new votes",
+    votes_disabled: "Stopped accepting new votes (votes already in progress can still be closed)",
+    cleanup_channels_preview: "Found {} empty VCs. Run again with confirm set to True to delete them\n{}",
+    cleanup_channels_done: "Deleted {} empty VCs ({} failed)",
+    config_restart_required: " (takes effect after a restart)",
+    config_reload_failed: "Could not reload the config (the config was not changed)\n{}",
+
+    no_permission: "You don't have permission to use this command",
+    cooldown: "You are on cooldown. Please wait {} more seconds",
+    no_move_permission: "You don't have permission to move members",
+    owner_only: "Only the bot owner can use this command",
+    selftest_category_not_configured: "selftest_category is not configured",
+    selftest_result: "Self-test results\n{}",
+    selftest_create_channel: "Create VC ({})",
+    selftest_rename_channel: "Rename VC",
+    selftest_move_permission: "Move Members permission ({})",
+    selftest_delete_channel: "Delete VC ({})",
+    channel_not_specified: "No channel was specified",
+    channel_not_found: "Could not get the channel",
+    users_not_specified: "No users were specified",
+    users_mention_required: "Specify the users to move by mentioning them",
+    guild_not_found: "Server not found",
+    guild_fetch_failed: "Failed to get the server",
+    sender_not_found: "Could not get the user who sent the command",
+    response_failed: "Failed to respond to the command",
+    enabled_not_specified: "The enabled option was not specified",
+    guild_not_configured: "The bot has not been set up for this server yet. Ask an administrator to configure it",
+    guild_misconfigured: "The bot's settings for this server are invalid. Ask an administrator to check them",
+    not_in_voice: "You are not in a voice channel",
+    move_here_not_in_voice: "Join the voice channel you want everyone to move to, then run this command",
+    channel_not_created: "The VC was not created. Please try again later",
+    create_channel_no_permission: "The bot cannot create the VC because it lacks the Manage Channels permission. Ask an administrator to grant it",
+    category_full: "The category is full. The VC cannot be created, so delete unused VCs and try again",
+    created_channel_limit: "The bot has already created the maximum of {} VCs in this server. Please try again after unused VCs are deleted",
+    no_connect_permission: "You don't have permission to join the specified VC",
+    not_voice_channel: "Specify a voice channel as the destination",
+    category_not_allowed: "Only VCs in the following categories can be the destination: {}",
+    ignored_channel: "This channel is excluded",
+    source_channel_not_voice: "Only voice channels in this server can be gathered from",
+    lobby_not_configured: "The lobby VC is not configured. Ask an administrator to set it up",
+    already_in_lobby: "You are already in the lobby VC",
+    bot_missing_permissions: "The bot is missing required permissions in this channel: {}",
+    wrong_category: "This channel is not in the configured category",
+    channel_name_required: "Enter a channel name",
+    channel_name_not_allowed: "This channel name is not allowed",
+    channel_name_blocked: "The channel name contains a blocked word",
+    channel_name_too_long: "The channel name must be {} characters or fewer",
+    channel_name_invalid_chars: "The channel name contains characters that cannot be used, such as line breaks",
+    default_channel_name_invalid:
+        "The default channel name does not meet Discord's limits (1-100 characters)",
+    retarget_target_required: "Specify the new destination channel or channel name",
+    no_active_vote: "You don't have an active vote",
+    cancel_not_organizer: "Only the organizer can cancel the vote",
+    post_failed: "Failed to post the message",
+    vote_reaction_failed: "Could not add reactions to the vote message because Discord is busy. Please wait a moment and try again",
+
+    move_users_result: "Results of moving members to {}\n{}",
+    move_users_moved: "✅ Moved",
+    move_users_failed: "❌ Failed to move",
+    moved_with: "{} and {} members",
+    moved_by: "Members called by {} ({} people)",
+    arrived: "{} arrived!",
+    moved_result: "Moved {} to {}.",
+    moved_members_title: "Moved members",
+    random_groups_title: "Groups",
+    reason_field: "Reason",
+    move_failed_field: "Could not move",
+    no_connect_permission_field: "Not moved (no permission to join the VC)",
+    limit_to_ignored_field: "Not moved (not included in the allowed members)",
+    move_exempt_field: "Exempt from moving",
+    destination_full_field: "Not moved (the destination VC was full)",
+    and_more: "…and {} more",
+    dm_summary: "Moved members of {} to {}.\nMoved members: {}\nCould not move: {}",
+    moved_dm: "{}: You have been moved to {}.",
+    moved_dm_failed: "Could not send a DM to {} members",
+    none: "none",
+    return_soon: "Returning to {} soon.",
+    returned: "Returned {} members from {} to {}.",
+    webhook_name: "VC Move",
+};
+
+/// 対応している言語 (言語を追加するときはここに追加する)
+const LOCALES: &[&Strings] = &[&JA, &EN];
+
+/// 言語コードから文字列を取得
+pub fn lookup(locale: &str) -> Option<&'static Strings> {
+    LOCALES
+        .iter()
+        .copied()
+        .find(|strings| strings.locale == locale)
+}
+
+/// 対応している言語コードの一覧
+pub fn supported_locales() -> Vec<&'static str> {
+    LOCALES.iter().map(|strings| strings.locale).collect()
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+/// 進行中の投票と元のVCへ戻す予定を保存するファイル (再起動後に募集と戻す予定を再開するため)
+#[derive(Debug, Clone)]
+pub struct VoteStore {
+    /// 保存先のパス
+    path: PathBuf,
+    /// 投票と戻す予定を別々に保存しても上書きし合わないよう、読み書きを1つずつ行う
+    lock: Arc<Mutex<()>>,
+}
+
+/// 進行中の投票を保存する項目
+const VOTES_KEY: &str = "votes";
+
+/// 元のVCへ戻す予定を保存する項目
+const RETURNS_KEY: &str = "returns";
+
+impl VoteStore {
+    /// コンストラクタ (basedirの votes.json に保存する)
+    pub fn new(basedir: &str) -> Self {
+        Self {
+            path: PathBuf::from(format!("{}/votes.json", basedir)),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// 保存した投票を読み込む (ファイルがなければ空)
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.load_items(VOTES_KEY)
+    }
+
+    /// 投票を保存する
+    pub fn save<T: Serialize>(&self, votes: &[T]) -> Result<()> {
+        self.save_items(VOTES_KEY, votes)
+    }
+
+    /// 保存した元のVCへ戻す予定を読み込む (ファイルがなければ空)
+    pub fn load_returns<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        self.load_items(RETURNS_KEY)
+    }
+
+    /// 元のVCへ戻す予定を保存する
+    pub fn save_returns<T: Serialize>(&self, returns: &[T]) -> Result<()> {
+        self.save_items(RETURNS_KEY, returns)
+    }
+
+    /// 保存した項目を読み込む
+    fn load_items<T: DeserializeOwned>(&self, key: &str) -> Result<Vec<T>> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        match self.read()?.remove(key) {
+            Some(items) => {
+                serde_json::from_value(items).context("投票の保存ファイルの形式が正しくありません")
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// 項目を保存する (ほかの項目はそのまま残す)
+    fn save_items<T: Serialize>(&self, key: &str, items: &[T]) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        // 読み込めないファイルは上書きする
+        let mut file = self.read().unwrap_or_default();
+        file.insert(
+            key.to_string(),
+            serde_json::to_value(items).context("投票の変換に失敗")?,
+        );
+        let json = serde_json::to_string_pretty(&file).context("投票の変換に失敗")?;
+        // 書き込み中に終了してもファイルが壊れないよう、一時ファイルに書いてから置き換える
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, json).context("投票の保存ファイルの書き込みに失敗")?;
+        fs::rename(&temp_path, &self.path).context("投票の保存ファイルの置き換えに失敗")?;
+        Ok(())
+    }
+
+    /// 保存ファイルを読み込む (ファイルがなければ空)
+    ///
+    /// 以前の形式 (投票の配列のみ) のファイルは投票として読み込む
+    fn read(&self) -> Result<Map<String, Value>> {
+        if !self.path.exists() {
+            return Ok(Map::new());
+        }
+        let json = fs::read_to_string(&self.path).context("投票の保存ファイルの読み込みに失敗")?;
+        match serde_json::from_str(&json).context("投票の保存ファイルの形式が正しくありません")?
+        {
+            Value::Object(file) => Ok(file),
+            Value::Array(votes) => Ok(Map::from_iter([(
+                VOTES_KEY.to_string(),
+                Value::Array(votes),
+            )])),
+            _ => Err(anyhow!("投票の保存ファイルの形式が正しくありません")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テストごとに空の保存先を作成
+    fn test_store(name: &str) -> VoteStore {
+        let dir = std::env::temp_dir().join(format!(
+            "discord-vc-vote-move-test-{}-store-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("保存先の作成に失敗");
+        VoteStore::new(&dir.to_string_lossy())
+    }
+
+    /// 投票と戻す予定は別々に保存しても互いに残る
+    #[test]
+    fn votes_and_returns_are_saved_separately() {
+        let store = test_store("separate");
+        store.save(&[1, 2]).unwrap();
+        store.save_returns(&["return"]).unwrap();
+        store.save(&[3]).unwrap();
+
+        assert_eq!(store.load::<i32>().unwrap(), vec![3]);
+        assert_eq!(store.load_returns::<String>().unwrap(), vec!["return"]);
+    }
+
+    /// 以前の形式 (投票の配列のみ) のファイルも投票として読み込む
+    #[test]
+    fn legacy_file_is_loaded_as_votes() {
+        let store = test_store("legacy");
+        fs::write(&store.path, "[1, 2]").unwrap();
+
+        assert_eq!(store.load::<i32>().unwrap(), vec![1, 2]);
+        assert!(store.load_returns::<i32>().unwrap().is_empty());
+    }
+}